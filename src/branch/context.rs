@@ -155,6 +155,14 @@ impl Context {
         Ok(())
     }
 
+    /// Fails if the branch is protected in the project manifest
+    pub async fn check_protected(&self, branch: &str, force: bool) -> anyhow::Result<()> {
+        if let Some(project) = self.get_project().await? {
+            project.check_protected_branch(branch, force)?;
+        }
+        Ok(())
+    }
+
     pub async fn get_project(&self) -> anyhow::Result<Option<project::Context>> {
         if let Some(ctx) = &*self.project_ctx_cache.lock().unwrap() {
             return Ok(Some(ctx.clone()));
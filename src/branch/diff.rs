@@ -0,0 +1,286 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use dissimilar::Chunk;
+use edgeql_parser::helpers::quote_name;
+use uuid::Uuid;
+
+use crate::branch::connections::connect_if_branch_exists;
+use crate::branch::context::Context;
+use crate::commands::Options;
+use crate::commands::helpers::quote_namespaced;
+use crate::connect::{Connection, Connector};
+use crate::migrations::create::CurrentMigration;
+use crate::print::{self, Highlight};
+
+const OBJECTS_QUERY: &str = r###"
+    WITH MODULE schema,
+        names := DISTINCT {
+            (SELECT ObjectType
+             FILTER NOT .is_compound_type AND NOT .is_from_alias).name,
+            ScalarType.name,
+            Alias.name,
+            Global.name,
+            Function.name,
+            (SELECT Constraint FILTER .is_abstract).name,
+        }
+    SELECT names
+    FILTER NOT re_test(
+        "^(?:std|schema|math|sys|cfg|cal|stdgraphql|ext)::", names)
+    ORDER BY names
+"###;
+
+pub async fn main(
+    cmd: &Command,
+    context: &Context,
+    connection: &mut Connection,
+    opts: &Options,
+) -> anyhow::Result<()> {
+    let (from, to) = match &cmd.other {
+        Some(other) => (cmd.branch.clone(), other.clone()),
+        None => (
+            context.get_current_branch(connection).await?,
+            cmd.branch.clone(),
+        ),
+    };
+    if from == to {
+        anyhow::bail!("Cannot diff the branch '{from}' with itself");
+    }
+
+    let mut connector = opts.conn_params.clone();
+    let mut from_connection = connect(&mut connector, &from).await?;
+    let mut to_connection = connect(&mut connector, &to).await?;
+
+    let from_objects = describe_objects(&mut from_connection).await?;
+    let to_objects = describe_objects(&mut to_connection).await?;
+
+    let names: BTreeSet<&String> = from_objects.keys().chain(to_objects.keys()).collect();
+    let mut changed = false;
+    for name in names {
+        let old = from_objects.get(name).map(|s| &s[..]).unwrap_or("");
+        let new = to_objects.get(name).map(|s| &s[..]).unwrap_or("");
+        if old == new {
+            continue;
+        }
+        if !changed {
+            println!("--- {from}");
+            println!("+++ {to}");
+            changed = true;
+        }
+        let status = if old.is_empty() {
+            "added"
+        } else if new.is_empty() {
+            "removed"
+        } else {
+            "changed"
+        };
+        println!("{}", format!("@@ {name} ({status}) @@").emphasized());
+        for line in diff_lines(old, new) {
+            match line {
+                Line::Equal(text) => println!(" {text}"),
+                Line::Insert(text) => println!("+{}", text.success()),
+                Line::Delete(text) => println!("-{}", text.danger()),
+            }
+        }
+    }
+    if !changed {
+        eprintln!("Schemas of '{from}' and '{to}' are identical.");
+        return Ok(());
+    }
+
+    if cmd.ddl {
+        let ddl = Box::pin(migration_ddl(
+            &from,
+            &mut from_connection,
+            &mut to_connection,
+            &mut connector,
+        ))
+        .await?;
+        println!();
+        println!(
+            "{}",
+            format!("# DDL to migrate '{from}' to '{to}'").emphasized()
+        );
+        for statement in ddl {
+            println!("{statement}");
+        }
+    }
+    Ok(())
+}
+
+/// Shows schema differences between two branches.
+///
+/// With a single branch, compares the current branch with it.
+#[derive(clap::Args, Debug, Clone)]
+pub struct Command {
+    /// The branch to compare with (or the first of two branches).
+    pub branch: String,
+
+    /// The second branch to compare. If specified, `branch` is compared
+    /// with this one instead of the current branch.
+    pub other: Option<String>,
+
+    /// Also show the DDL the server would need to migrate the first branch
+    /// to the second one. Uses a temporary branch, which is dropped afterwards.
+    #[arg(long)]
+    pub ddl: bool,
+}
+
+async fn connect(connector: &mut Connector, branch: &str) -> anyhow::Result<Connection> {
+    match connect_if_branch_exists(connector.branch(branch)?).await? {
+        Some(connection) => Ok(connection),
+        None => anyhow::bail!("The branch '{branch}' doesn't exist"),
+    }
+}
+
+/// Returns SDL of each top-level user-defined object, keyed by name
+async fn describe_objects(cli: &mut Connection) -> anyhow::Result<BTreeMap<String, String>> {
+    let names = cli.query::<String, _>(OBJECTS_QUERY, &()).await?;
+    let mut objects = BTreeMap::new();
+    for name in names {
+        let sdl = cli
+            .query_required_single::<String, _>(
+                &format!("DESCRIBE OBJECT {} AS SDL", quote_namespaced(&name)),
+                &(),
+            )
+            .await?;
+        objects.insert(name, sdl.trim_end().to_string());
+    }
+    Ok(objects)
+}
+
+/// Populates a migration from the schema of `from` to the schema of `to` on
+/// a throwaway schema-only clone of `from` and returns its statements.
+async fn migration_ddl(
+    from: &str,
+    from_connection: &mut Connection,
+    to_connection: &mut Connection,
+    connector: &mut Connector,
+) -> anyhow::Result<Vec<String>> {
+    let target_sdl = to_connection
+        .query_required_single::<String, _>("DESCRIBE SCHEMA AS SDL", &())
+        .await?;
+
+    let temp_branch = Uuid::new_v4().to_string();
+    let (status, _warnings) = from_connection
+        .execute(
+            &format!(
+                "create schema branch {} from {}",
+                quote_name(&temp_branch),
+                quote_name(from)
+            ),
+            &(),
+        )
+        .await?;
+    print::completion(status);
+
+    let result = async {
+        let mut temp_connection = connector.branch(&temp_branch)?.connect().await?;
+        temp_connection
+            .execute(&format!("START MIGRATION TO {{ {target_sdl} }}"), &())
+            .await?;
+        temp_connection.execute("POPULATE MIGRATION", &()).await?;
+        let migration = temp_connection
+            .query_required_single::<CurrentMigration, _>("DESCRIBE CURRENT MIGRATION AS JSON", &())
+            .await?;
+        temp_connection.execute("ABORT MIGRATION", &()).await?;
+        if !migration.complete {
+            print::warn!(
+                "The server could not derive the full migration without \
+                 user input; the DDL below is incomplete."
+            );
+        }
+        anyhow::Ok(migration.confirmed)
+    }
+    .await;
+
+    match from_connection
+        .execute(
+            &format!("drop branch {} force", quote_name(&temp_branch)),
+            &(),
+        )
+        .await
+    {
+        Ok((status, _warnings)) => print::completion(status),
+        // don't hide the original error
+        Err(e) if result.is_err() => {
+            log::error!("Cannot drop temporary branch {temp_branch:?}: {e:#}");
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    result
+}
+
+#[derive(Debug, PartialEq)]
+enum Line<'a> {
+    Equal(&'a str),
+    Insert(&'a str),
+    Delete(&'a str),
+}
+
+/// Line-level diff. Each distinct line is mapped to a single character, so
+/// that the character-level diff never splits lines.
+fn diff_lines<'a>(old: &'a str, new: &'a str) -> Vec<Line<'a>> {
+    let mut ids = BTreeMap::new();
+    let mut lines = Vec::new();
+    let mut encode = |text: &'a str| -> String {
+        if text.is_empty() {
+            return String::new();
+        }
+        text.split('\n')
+            .map(|line| {
+                *ids.entry(line).or_insert_with(|| {
+                    lines.push(line);
+                    char::from_u32(0xF0000 + lines.len() as u32 - 1).expect("valid char")
+                })
+            })
+            .collect()
+    };
+    let old_encoded = encode(old);
+    let new_encoded = encode(new);
+
+    let decode = |block: &str| -> Vec<&'a str> {
+        block
+            .chars()
+            .map(|c| lines[(c as u32 - 0xF0000) as usize])
+            .collect()
+    };
+    let mut result = Vec::new();
+    for chunk in dissimilar::diff(&old_encoded, &new_encoded) {
+        match chunk {
+            Chunk::Equal(block) => result.extend(decode(block).into_iter().map(Line::Equal)),
+            Chunk::Insert(block) => result.extend(decode(block).into_iter().map(Line::Insert)),
+            Chunk::Delete(block) => result.extend(decode(block).into_iter().map(Line::Delete)),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Line, diff_lines};
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            diff_lines(
+                "type User {\n    name: str;\n};",
+                "type User {\n    name: str;\n    age: int32;\n};"
+            ),
+            vec![
+                Line::Equal("type User {"),
+                Line::Equal("    name: str;"),
+                Line::Insert("    age: int32;"),
+                Line::Equal("};"),
+            ]
+        );
+    }
+
+    #[test]
+    fn added() {
+        assert_eq!(
+            diff_lines("", "scalar type Slug extending str;"),
+            vec![Line::Insert("scalar type Slug extending str;")]
+        );
+    }
+}
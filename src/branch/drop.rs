@@ -1,6 +1,7 @@
 use crate::branch::context::Context;
+use crate::branch::snapshot;
 use crate::branding::BRANDING_CLI_CMD;
-use crate::commands::ExitCode;
+use crate::commands::{ExitCode, Options};
 use crate::connect::Connection;
 use crate::portable::exit_codes;
 use crate::{print, question};
@@ -9,6 +10,7 @@ pub async fn main(
     options: &Command,
     context: &Context,
     connection: &mut Connection,
+    cli_opts: &Options,
 ) -> anyhow::Result<()> {
     let current_branch = context.get_current_branch(connection).await?;
 
@@ -19,6 +21,10 @@ pub async fn main(
         );
     }
 
+    context
+        .check_protected(&options.target_branch, options.force_protected)
+        .await?;
+
     if !options.non_interactive {
         let q = question::Confirm::new_dangerous(format!(
             "Do you really want to drop the branch {:?}?",
@@ -30,6 +36,21 @@ pub async fn main(
         }
     }
 
+    if !options.no_snapshot {
+        match snapshot::take(&options.target_branch, cli_opts, connection).await {
+            Ok(_) => {}
+            // a branch can't be copied while other sessions use it, which
+            // is what `--force` is for
+            Err(e) if options.force => {
+                print::warn!(
+                    "Cannot save a snapshot of {:?}, dropping it anyway: {e:#}",
+                    options.target_branch
+                );
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
     let mut statement = format!(
         "drop branch {}",
         edgeql_parser::helpers::quote_name(&options.target_branch)
@@ -57,6 +78,16 @@ pub struct Command {
     pub non_interactive: bool,
 
     /// Close any existing connections to the branch before dropping it.
+    /// The branch is dropped even if a snapshot can't be saved because of
+    /// these connections.
     #[arg(long)]
     pub force: bool,
+
+    /// Do not save a snapshot of the branch before dropping it.
+    #[arg(long)]
+    pub no_snapshot: bool,
+
+    /// Proceed even if the branch is protected in the project manifest.
+    #[arg(long)]
+    pub force_protected: bool,
 }
@@ -1,32 +1,267 @@
+use std::time::{Duration, SystemTime};
+
+use gel_protocol::common::{
+    Capabilities, Cardinality, CompilationOptions, InputLanguage, IoFormat,
+};
+use gel_tokio::dsn::DEFAULT_BRANCH_NAME_CREATE;
+use indexmap::IndexMap;
+use prettytable::{Cell, Row, Table};
+use termimad::crossterm::style::Stylize;
+use uuid::Uuid;
+
+use crate::branch::connections::connect_if_branch_exists;
 use crate::branch::context::Context;
+use crate::commands::Options;
 use crate::connect::Connection;
-use termimad::crossterm::style::Stylize;
+use crate::migrations::db_migration::{self, DBMigration};
+use crate::table;
 
 pub async fn main(
-    _options: &Command,
+    options: &Command,
     context: &Context,
     connection: &mut Connection,
+    opts: &Options,
 ) -> anyhow::Result<()> {
     let current_branch = context.get_current_branch(connection).await?;
 
-    let branches: Vec<String> = connection
+    let branches: Vec<(String, Uuid)> = connection
         .query(
-            "SELECT (SELECT sys::Database FILTER NOT .builtin).name",
+            "FOR b IN (SELECT sys::Database FILTER NOT .builtin) UNION (b.name, b.id)",
             &(),
         )
         .await?;
 
-    for branch in branches {
-        if current_branch == branch {
-            println!("{} - Current", branch.green());
-        } else {
-            println!("{branch}");
+    if !options.verbose && !options.json {
+        for (branch, _) in branches {
+            if current_branch == branch {
+                println!("{} - Current", branch.green());
+            } else {
+                println!("{branch}");
+            }
         }
+        return Ok(());
     }
 
+    let base = options
+        .base
+        .clone()
+        .unwrap_or_else(|| DEFAULT_BRANCH_NAME_CREATE.to_string());
+    let mut connector = opts.conn_params.clone();
+    let mut details = Vec::with_capacity(branches.len());
+    for (branch, id) in &branches {
+        let Some(mut conn) = connect_if_branch_exists(connector.branch(branch)?).await? else {
+            // dropped while we were listing
+            continue;
+        };
+        let migrations = db_migration::read_all(&mut conn, false, false).await?;
+        let size = sql_number(&mut conn, "SELECT pg_database_size(current_database())").await;
+        let connections = sql_number(
+            &mut conn,
+            "SELECT count(*) - 1 FROM pg_stat_activity WHERE datname = current_database()",
+        )
+        .await
+        .map(|n| n.max(0));
+        details.push((branch, id, migrations, size, connections));
+    }
+    let base_migrations = details
+        .iter()
+        .find(|(name, ..)| **name == base)
+        .map(|(_, _, migrations, ..)| migrations.keys().cloned().collect::<Vec<_>>());
+
+    let items = details
+        .iter()
+        .map(|(name, id, migrations, size, connections)| {
+            let (ahead, behind) = match &base_migrations {
+                Some(base_migrations) if **name != base => {
+                    let (ahead, behind) = divergence(migrations, base_migrations);
+                    (Some(ahead), Some(behind))
+                }
+                _ => (None, None),
+            };
+            BranchInfo {
+                name: name.to_string(),
+                current: **name == current_branch,
+                created: created_at(id),
+                migrations: migrations.len(),
+                last_migration: migrations.keys().last().cloned(),
+                ahead,
+                behind,
+                size: *size,
+                connections: *connections,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if options.json {
+        println!("{}", serde_json::to_string_pretty(&items)?);
+        return Ok(());
+    }
+
+    let divergence_title = format!("Ahead / Behind {base}");
+    let mut table = Table::new();
+    table.set_format(*table::FORMAT);
+    table.set_titles(Row::new(
+        [
+            "Name",
+            "Created",
+            "Migrations",
+            "Last Migration",
+            divergence_title.as_str(),
+            "Size",
+            "Connections",
+        ]
+        .iter()
+        .map(|x| table::header_cell(x))
+        .collect(),
+    ));
+    for item in items {
+        let name = if item.current {
+            format!("{} (current)", item.name)
+        } else {
+            item.name
+        };
+        let divergence = match (item.ahead, item.behind) {
+            (Some(ahead), Some(behind)) => format!("+{ahead} / -{behind}"),
+            _ => String::from("-"),
+        };
+        table.add_row(Row::new(vec![
+            Cell::new(&name),
+            Cell::new(item.created.as_deref().unwrap_or("-")),
+            Cell::new(&item.migrations.to_string()),
+            Cell::new(item.last_migration.as_deref().unwrap_or("initial")),
+            Cell::new(&divergence),
+            Cell::new(&item.size.map(format_size).unwrap_or_else(|| "-".into())),
+            Cell::new(
+                &item
+                    .connections
+                    .map(|n| n.to_string())
+                    .unwrap_or_else(|| "-".into()),
+            ),
+        ]));
+    }
+    table.printstd();
+
     Ok(())
 }
 
 /// List all branches.
 #[derive(clap::Args, Debug, Clone)]
-pub struct Command {}
+pub struct Command {
+    /// Show the creation time, the migration head, the size, the number of
+    /// other connections and how far each branch has diverged from the base
+    /// branch.
+    #[arg(short = 'v', long)]
+    pub verbose: bool,
+
+    /// Output the verbose information in JSON format.
+    #[arg(long)]
+    pub json: bool,
+
+    /// The branch to compare migrations with (`main` by default).
+    #[arg(long, value_name = "BRANCH")]
+    pub base: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct BranchInfo {
+    name: String,
+    current: bool,
+    /// Creation time of the branch (RFC 3339), if known
+    created: Option<String>,
+    migrations: usize,
+    last_migration: Option<String>,
+    /// Number of migrations that are not in the base branch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ahead: Option<usize>,
+    /// Number of migrations of the base branch that are missing
+    #[serde(skip_serializing_if = "Option::is_none")]
+    behind: Option<usize>,
+    /// Approximate size of the branch on disk in bytes (including its
+    /// schema), if the server allows reading it
+    size: Option<i64>,
+    /// Number of connections to the branch besides the one used for
+    /// listing, if the server allows reading it
+    connections: Option<i64>,
+}
+
+/// Branch ids are time-based UUIDs generated when the branch is created
+pub fn creation_time(id: &Uuid) -> Option<SystemTime> {
+    let (secs, nanos) = id.get_timestamp()?.to_unix();
+    Some(SystemTime::UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+fn created_at(id: &Uuid) -> Option<String> {
+    let time = creation_time(id)?;
+    Some(humantime::format_rfc3339_seconds(time).to_string())
+}
+
+fn format_size(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Runs a query returning a single number using the server's SQL
+/// interface, returns `None` if this is not supported
+async fn sql_number(conn: &mut Connection, query: &str) -> Option<i64> {
+    let opts = CompilationOptions {
+        implicit_limit: None,
+        implicit_typenames: false,
+        implicit_typeids: false,
+        explicit_objectids: true,
+        allow_capabilities: Capabilities::empty(),
+        input_language: InputLanguage::SQL,
+        io_format: IoFormat::Binary,
+        expected_cardinality: Cardinality::AtMostOne,
+    };
+    let result = async {
+        let desc = conn.parse(&opts, query).await?;
+        let mut stream = conn
+            .execute_stream::<i64, _>(&opts, query, &desc, &())
+            .await?;
+        let number = stream.next_element().await;
+        stream.complete().await?;
+        Ok::<_, gel_errors::Error>(number)
+    }
+    .await;
+    match result {
+        Ok(number) => number,
+        Err(e) => {
+            log::debug!("Cannot run {query:?}: {e:#}");
+            None
+        }
+    }
+}
+
+/// Returns the number of migrations after the last common one, on each side
+fn divergence(migrations: &IndexMap<String, DBMigration>, base: &[String]) -> (usize, usize) {
+    let common = migrations
+        .keys()
+        .zip(base)
+        .take_while(|(a, b)| a == b)
+        .count();
+    (migrations.len() - common, base.len() - common)
+}
+
+#[cfg(test)]
+mod test {
+    use super::format_size;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(8 * 1024 * 1024), "8.0 MiB");
+        assert_eq!(format_size(3 << 40), "3.0 TiB");
+        assert_eq!(format_size(5000 << 40), "5000.0 TiB");
+    }
+}
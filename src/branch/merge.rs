@@ -1,11 +1,18 @@
-use crate::branch::connections::connect_if_branch_exists;
+use crate::branch::connections::{connect_if_branch_exists, get_connection_to_modify};
 use crate::branch::context::Context;
-use crate::commands::Options;
+use crate::branch::rebase::{clone_target_branch, rename_temp_to_source};
+use crate::branch::snapshot;
+use crate::commands::{ExitCode, Options};
 use crate::connect::Connection;
 use crate::migrations;
 use crate::migrations::merge::{
-    apply_merge_migration_files, get_merge_migrations, write_merge_migrations,
+    MergeMigrations, apply_combined_migration_file, apply_merge_migration_files,
+    backup_migration_files, get_merge_migrations, restore_migration_files, write_merge_migrations,
 };
+use crate::migrations::options::MigrationConfig;
+use crate::portable::exit_codes;
+use crate::print::{self, Highlight};
+use crate::question;
 
 pub async fn main(
     cmd: &Command,
@@ -34,6 +41,30 @@ pub async fn main(
     let mut merge_migrations =
         get_merge_migrations(source_connection, &mut target_connection).await?;
 
+    if !merge_migrations.is_fast_forward() {
+        if !cmd.three_way {
+            anyhow::bail!(
+                "Cannot complete fast-forward merge, the histories of {0} and {1} \
+                 are incompatible. Try rebasing {1} onto {0}, or use `--three-way`",
+                source_connection.database(),
+                target_connection.database()
+            )
+        }
+        drop(target_connection);
+        context
+            .check_protected(&current_branch, cmd.force_protected)
+            .await?;
+        return Box::pin(three_way_merge(
+            cmd,
+            &current_branch,
+            source_connection,
+            &migration_context,
+            &mut merge_migrations,
+            opts,
+        ))
+        .await;
+    }
+
     eprintln!(
         "Merging {} migration(s) into '{}'...",
         merge_migrations.target_migrations.len(),
@@ -54,12 +85,197 @@ pub async fn main(
 }
 
 /// Merges a branch into this one via a fast-forward merge.
+///
+/// With `--three-way`, branches that both have new migrations since their
+/// last common one can be merged too: the target's migrations are replayed
+/// and the current branch's changes are recomputed from the schema files as
+/// a single new migration on top of them. In that case the current branch is
+/// replaced with the merged clone of the target branch, so data stored in it
+/// is lost (a snapshot is saved first).
 #[derive(clap::Args, Clone, Debug)]
 pub struct Command {
     /// The branch to merge into this one.
     pub target_branch: String,
 
-    /// Skip applying migrations generated from the merge.
-    #[arg(long)]
+    /// Skip applying migrations generated from the merge. Not supported
+    /// with `--three-way`, which always replaces the current branch with a
+    /// migrated one.
+    #[arg(long, conflicts_with = "three_way")]
     pub no_apply: bool,
+
+    /// Do a three-way merge if the current branch has migrations that the
+    /// target branch doesn't have. This replaces the current branch, so its
+    /// data is lost.
+    #[arg(long)]
+    pub three_way: bool,
+
+    /// Allow a three-way merge to replace the current branch without
+    /// confirmation. Required with `--non-interactive`.
+    #[arg(long, requires = "three_way")]
+    pub allow_data_loss: bool,
+
+    /// Do not ask questions. Only safe schema changes are recomputed in a
+    /// three-way merge.
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Do not save a snapshot of the current branch before a three-way merge
+    /// replaces it.
+    #[arg(long, requires = "three_way")]
+    pub no_snapshot: bool,
+
+    /// Proceed with a three-way merge even if the current branch is
+    /// protected in the project manifest.
+    #[arg(long)]
+    pub force_protected: bool,
+}
+
+async fn three_way_merge(
+    cmd: &Command,
+    current_branch: &str,
+    source_connection: &mut Connection,
+    migration_context: &migrations::Context,
+    merge_migrations: &mut MergeMigrations,
+    opts: &Options,
+) -> anyhow::Result<()> {
+    let last_common = merge_migrations
+        .base_migrations
+        .last()
+        .map(|v| v.0.as_str())
+        .unwrap_or("initial");
+    eprintln!("Last common migration is {}", last_common.success());
+    eprintln!(
+        "Since then, there are:\n- {} new migration(s) on '{}',\n- {} migration(s) \
+         on '{current_branch}' to recompute as a single migration",
+        merge_migrations
+            .target_migrations
+            .len()
+            .to_string()
+            .success(),
+        cmd.target_branch,
+        merge_migrations
+            .source_migrations
+            .len()
+            .to_string()
+            .success(),
+    );
+    eprintln!(
+        "The schema files are used as the merged schema, so they should \
+         already contain the changes of both branches."
+    );
+    if cmd.non_interactive && !cmd.allow_data_loss {
+        anyhow::bail!(
+            "Three-way merge replaces '{current_branch}' and its data is lost. \
+             Use `--allow-data-loss` to proceed non-interactively"
+        );
+    }
+    if !cmd.allow_data_loss {
+        eprintln!(
+            "Branch '{current_branch}' will be replaced with the merged branch, \
+             currently stored data {}.",
+            "will be lost".emphasized()
+        );
+        let q = question::Confirm::new_dangerous("Do you want to continue?");
+        if !source_connection.ping_while(q.async_ask()).await? {
+            print::error!("Canceled.");
+            Err(ExitCode::new(exit_codes::NOT_CONFIRMED))?;
+        }
+    }
+
+    if !cmd.no_snapshot {
+        snapshot::take(current_branch, opts, source_connection).await?;
+    }
+
+    let backup = backup_migration_files(migration_context).await?;
+    let temp_branch = clone_target_branch(&cmd.target_branch, source_connection).await?;
+
+    let mut connector = opts.conn_params.clone();
+    let result = async {
+        let mut temp_connection = connector.branch(&temp_branch)?.connect().await?;
+        write_merge_migrations(migration_context, merge_migrations).await?;
+
+        eprintln!("\nRecomputing changes of '{current_branch}'...");
+        let create = migrations::create::Command {
+            cfg: MigrationConfig {
+                schema_dir: Some(migration_context.schema_dir.clone()),
+            },
+            squash: false,
+            from: None,
+            to: None,
+            non_interactive: cmd.non_interactive,
+            allow_unsafe: false,
+            allow_empty: false,
+            debug_print_queries: false,
+            debug_print_err: false,
+            quiet: false,
+            expert: false,
+        };
+        match migrations::create::run_inner(migration_context, &create, &mut temp_connection).await
+        {
+            Ok(_) => {}
+            // no changes: the current branch has nothing the target lacks
+            Err(e) if e.downcast_ref::<ExitCode>().is_some_and(|c| c.code() == 4) => {}
+            Err(e) => return Err(e),
+        }
+
+        eprintln!("Applying migrations...");
+        apply_combined_migration_file(merge_migrations, migration_context, &mut temp_connection)
+            .await?;
+        anyhow::Ok(temp_connection)
+    }
+    .await;
+
+    let mut temp_connection = match result {
+        Ok(connection) => connection,
+        Err(e) => {
+            // cleanup errors are logged, so that they don't hide the original error
+            if let Err(err) = restore_migration_files(migration_context, backup.path()).await {
+                log::error!("Cannot restore migration files: {err:#}");
+            }
+
+            eprintln!("Cleaning up cloned branch...");
+            if let Err(err) = drop_temp_branch(&temp_branch, opts, source_connection).await {
+                log::error!("Cannot drop temporary branch {temp_branch:?}: {err:#}");
+            }
+            return Err(e);
+        }
+    };
+
+    eprintln!("\nReplacing '{current_branch}' with merged version...");
+    let (status, _warnings) = temp_connection
+        .execute(
+            &format!(
+                "drop branch {} force",
+                edgeql_parser::helpers::quote_name(current_branch)
+            ),
+            &(),
+        )
+        .await?;
+    print::completion(status);
+    rename_temp_to_source(&temp_branch, current_branch, opts, &mut temp_connection).await?;
+
+    eprintln!("Done!");
+    Ok(())
+}
+
+async fn drop_temp_branch(
+    temp_branch: &str,
+    opts: &Options,
+    source_connection: &mut Connection,
+) -> anyhow::Result<()> {
+    let mut drop_connection =
+        get_connection_to_modify(temp_branch, opts, source_connection).await?;
+    let (status, _warnings) = drop_connection
+        .connection
+        .execute(
+            &format!(
+                "drop branch {} force",
+                edgeql_parser::helpers::quote_name(temp_branch)
+            ),
+            &(),
+        )
+        .await?;
+    print::completion(status);
+    drop_connection.clean().await?;
+    Ok(())
 }
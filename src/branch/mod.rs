@@ -2,12 +2,17 @@ mod connections;
 pub mod context;
 pub mod create;
 pub mod current;
+pub mod diff;
 pub mod drop;
 pub mod list;
 pub mod merge;
+pub mod prune;
 pub mod rebase;
 pub mod rename;
+pub mod restore_snapshot;
+pub mod snapshot;
 pub mod switch;
+pub mod sync_git;
 pub mod wipe;
 
 use futures_util::FutureExt;
@@ -22,7 +27,10 @@ pub async fn run(
     options: &Options,
     conn: Option<&mut Connection>,
 ) -> anyhow::Result<CommandResult> {
-    let read_only = matches!(&cmd, Subcommand::List(..) | Subcommand::Current(..));
+    let read_only = matches!(
+        &cmd,
+        Subcommand::List(..) | Subcommand::Current(..) | Subcommand::Diff(..)
+    );
     let context = context::Context::new(
         options.instance_name.as_ref(),
         options.skip_hooks,
@@ -37,8 +45,11 @@ pub async fn run(
         Subcommand::Switch(switch) => {
             return switch::run(switch, &context, &mut connector).boxed().await;
         }
+        Subcommand::SyncGit(sync) => {
+            return sync_git::run(sync, &context, &mut connector).boxed().await;
+        }
         Subcommand::Wipe(wipe) => {
-            wipe::main(wipe, &context, &mut connector).await?;
+            wipe::main(wipe, &context, &mut connector, options).await?;
             return Ok(CommandResult::default());
         }
         _ => {}
@@ -58,14 +69,19 @@ pub async fn run(
     match cmd {
         Subcommand::Current(cmd) => current::run(cmd, &context, conn).await?,
         Subcommand::Create(cmd) => create::run(cmd, &context, conn).await?,
-        Subcommand::Drop(cmd) => drop::main(cmd, &context, conn).await?,
-        Subcommand::List(cmd) => list::main(cmd, &context, conn).await?,
+        Subcommand::Drop(cmd) => drop::main(cmd, &context, conn, options).await?,
+        Subcommand::List(cmd) => list::main(cmd, &context, conn, options).await?,
         Subcommand::Rename(cmd) => return rename::run(cmd, &context, conn, options).await,
         Subcommand::Rebase(cmd) => Box::pin(rebase::main(cmd, &context, conn, options)).await?,
         Subcommand::Merge(cmd) => merge::main(cmd, &context, conn, options).await?,
+        Subcommand::RestoreSnapshot(cmd) => {
+            restore_snapshot::main(cmd, &context, conn, options).await?
+        }
+        Subcommand::Prune(cmd) => prune::main(cmd, &context, conn, options).await?,
+        Subcommand::Diff(cmd) => Box::pin(diff::main(cmd, &context, conn, options)).await?,
 
         // handled earlier
-        Subcommand::Switch(_) | Subcommand::Wipe(_) => unreachable!(),
+        Subcommand::Switch(_) | Subcommand::SyncGit(_) | Subcommand::Wipe(_) => unreachable!(),
     }
 
     Ok(CommandResult::default())
@@ -89,12 +105,16 @@ pub struct CommandResult {
 pub enum Subcommand {
     Create(create::Command),
     Switch(switch::Command),
+    SyncGit(sync_git::Command),
     List(list::Command),
     Current(current::Command),
     Rebase(rebase::Command),
     Merge(merge::Command),
+    Diff(diff::Command),
     Rename(rename::Command),
     Drop(drop::Command),
+    Prune(prune::Command),
+    RestoreSnapshot(restore_snapshot::Command),
     Wipe(wipe::Command),
 }
 
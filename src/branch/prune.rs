@@ -0,0 +1,349 @@
+use std::collections::BTreeSet;
+use std::time::{Duration, SystemTime};
+
+use edgeql_parser::helpers::quote_name;
+use uuid::Uuid;
+
+use crate::branch::connections::connect_if_branch_exists;
+use crate::branch::context::Context;
+use crate::branch::list::creation_time;
+use crate::branch::snapshot;
+use crate::commands::{ExitCode, Options};
+use crate::connect::Connection;
+use crate::migrations::db_migration;
+use crate::portable::exit_codes;
+use crate::print::{self, Highlight};
+use crate::{git, question};
+
+pub async fn main(
+    cmd: &Command,
+    context: &Context,
+    connection: &mut Connection,
+    opts: &Options,
+) -> anyhow::Result<()> {
+    if cmd.merged_into.is_none() && cmd.older_than.is_none() && !cmd.no_git_branch {
+        anyhow::bail!("Specify at least one of `--merged-into`, `--older-than`, `--no-git-branch`");
+    }
+    let current_branch = context.get_current_branch(connection).await?;
+    let project = context.get_project().await?;
+
+    let git_branches = if cmd.no_git_branch {
+        let Some(project) = &project else {
+            anyhow::bail!("`--no-git-branch` must be used within a project");
+        };
+        let project = project.manifest.project();
+        let names = git::git_branches().await?;
+        if names.is_empty() {
+            anyhow::bail!("No git branches found, refusing to prune all branches");
+        }
+        Some(
+            names
+                .iter()
+                .map(|name| project.map_git_branch(name))
+                .collect::<BTreeSet<_>>(),
+        )
+    } else {
+        None
+    };
+
+    let branches: Vec<(String, Uuid)> = connection
+        .query(
+            "FOR b IN (SELECT sys::Database FILTER NOT .builtin) UNION (b.name, b.id)",
+            &(),
+        )
+        .await?;
+
+    let mut connector = opts.conn_params.clone();
+    let base_migrations = if let Some(base) = &cmd.merged_into {
+        let Some(mut conn) = connect_if_branch_exists(connector.branch(base)?).await? else {
+            anyhow::bail!("The branch '{base}' doesn't exist");
+        };
+        let migrations = db_migration::read_all(&mut conn, false, false).await?;
+        Some(migrations.into_keys().collect::<Vec<_>>())
+    } else {
+        None
+    };
+
+    let protected = match &project {
+        Some(project) => branches
+            .iter()
+            .filter(|(name, _)| project.manifest.project().protected_by(name).is_some())
+            .map(|(name, _)| name.clone())
+            .collect(),
+        None => BTreeSet::new(),
+    };
+    let criteria = Criteria {
+        current_branch: &current_branch,
+        protected: &protected,
+        merged_into: cmd.merged_into.as_deref().zip(base_migrations.as_deref()),
+        older_than: cmd.older_than,
+        git_branches: git_branches.as_ref(),
+        now: SystemTime::now(),
+    };
+
+    let mut to_drop = Vec::new();
+    for (name, id) in branches {
+        if criteria.is_exempt(&name) {
+            continue;
+        }
+        let migrations = if criteria.merged_into.is_some() {
+            let Some(mut conn) = connect_if_branch_exists(connector.branch(&name)?).await? else {
+                // dropped while we were listing
+                continue;
+            };
+            let migrations = db_migration::read_all(&mut conn, false, false).await?;
+            Some(migrations.into_keys().collect())
+        } else {
+            None
+        };
+        let candidate = Candidate {
+            created: creation_time(&id),
+            name,
+            migrations,
+        };
+        if criteria.matches(&candidate) {
+            to_drop.push(candidate.name);
+        }
+    }
+
+    if to_drop.is_empty() {
+        eprintln!("No branches to prune.");
+        return Ok(());
+    }
+
+    eprintln!("Branches to prune:");
+    for branch in &to_drop {
+        eprintln!("  {}", branch.emphasized());
+    }
+    if cmd.dry_run {
+        return Ok(());
+    }
+
+    if !cmd.non_interactive {
+        let q = question::Confirm::new_dangerous(format!(
+            "Do you really want to drop {} branch(es)?",
+            to_drop.len()
+        ));
+        if !connection.ping_while(q.async_ask()).await? {
+            print::error!("Canceled by user.");
+            return Err(ExitCode::new(exit_codes::NOT_CONFIRMED).into());
+        }
+    }
+
+    for branch in &to_drop {
+        let mut statement = format!("drop branch {}", quote_name(branch));
+        if cmd.force {
+            statement.push_str(" force");
+        }
+        let (status, _warnings) = connection.execute(&statement, &()).await?;
+        print::completion(status);
+    }
+    Ok(())
+}
+
+/// Drops stale branches.
+///
+/// A branch is pruned if it matches all of the given criteria. The current
+/// branch, snapshots and branches that are protected in the project manifest
+/// are never pruned.
+///
+/// Unlike `branch drop`, no snapshots are saved: nothing would ever clean
+/// up snapshots of branches that no longer exist. Use `--dry-run` to review
+/// the branches first.
+#[derive(clap::Args, Debug, Clone)]
+pub struct Command {
+    /// Prune branches whose migration history is a prefix of this branch's
+    /// history (i.e. branches that are fully merged into it).
+    #[arg(long, value_name = "BRANCH")]
+    pub merged_into: Option<String>,
+
+    /// Prune branches created longer ago than this (e.g. `30days`,
+    /// `2weeks`).
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub older_than: Option<Duration>,
+
+    /// Prune branches that don't have a corresponding local git branch
+    /// (names are mapped using `git-branch-map` of the project manifest).
+    #[arg(long)]
+    pub no_git_branch: bool,
+
+    /// Only print the branches that would be dropped.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Drop the branches without asking for confirmation.
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Close any existing connections to the branches before dropping them.
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// What is known about a branch when deciding whether to prune it
+struct Candidate {
+    name: String,
+    /// `None` if the branch id is not time-based
+    created: Option<SystemTime>,
+    /// Migration ids, only read when `--merged-into` is used
+    migrations: Option<Vec<String>>,
+}
+
+struct Criteria<'a> {
+    current_branch: &'a str,
+    protected: &'a BTreeSet<String>,
+    /// The base branch and its migration ids
+    merged_into: Option<(&'a str, &'a [String])>,
+    older_than: Option<Duration>,
+    /// Branch names that correspond to local git branches
+    git_branches: Option<&'a BTreeSet<String>>,
+    now: SystemTime,
+}
+
+impl Criteria<'_> {
+    /// Branches that are never pruned
+    fn is_exempt(&self, name: &str) -> bool {
+        if name == self.current_branch || self.merged_into.is_some_and(|(base, _)| base == name) {
+            return true;
+        }
+        // snapshots have their own retention, see `snapshot::keep_snapshots`
+        if snapshot::is_snapshot(name) {
+            return true;
+        }
+        if self.protected.contains(name) {
+            log::info!("Skipping protected branch {name:?}");
+            return true;
+        }
+        false
+    }
+
+    fn matches(&self, branch: &Candidate) -> bool {
+        if let Some(git_branches) = self.git_branches {
+            if git_branches.contains(&branch.name) {
+                return false;
+            }
+        }
+        if let Some((_, base_migrations)) = self.merged_into {
+            let Some(migrations) = &branch.migrations else {
+                return false;
+            };
+            if !base_migrations.starts_with(migrations) {
+                return false;
+            }
+        }
+        if let Some(older_than) = self.older_than {
+            // branches of unknown age are kept
+            let Some(created) = branch.created else {
+                return false;
+            };
+            let age = self.now.duration_since(created).unwrap_or_default();
+            if age < older_than {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+    use std::time::{Duration, SystemTime};
+
+    use super::{Candidate, Criteria};
+
+    const DAY: Duration = Duration::from_secs(86400);
+
+    fn candidate(name: &str, age: Option<Duration>, migrations: &[&str]) -> Candidate {
+        let now = SystemTime::UNIX_EPOCH + 1000 * DAY;
+        Candidate {
+            name: name.into(),
+            created: age.map(|age| now - age),
+            migrations: Some(migrations.iter().map(|m| m.to_string()).collect()),
+        }
+    }
+
+    fn criteria<'a>(protected: &'a BTreeSet<String>) -> Criteria<'a> {
+        Criteria {
+            current_branch: "main",
+            protected,
+            merged_into: None,
+            older_than: None,
+            git_branches: None,
+            now: SystemTime::UNIX_EPOCH + 1000 * DAY,
+        }
+    }
+
+    #[test]
+    fn exempt() {
+        let protected = BTreeSet::from(["release-1".to_string()]);
+        let base = vec!["m1".to_string()];
+        let criteria = Criteria {
+            merged_into: Some(("staging", &base)),
+            ..criteria(&protected)
+        };
+        assert!(criteria.is_exempt("main"));
+        assert!(criteria.is_exempt("staging"));
+        assert!(criteria.is_exempt("release-1"));
+        assert!(criteria.is_exempt("snapshot--feature--20231114T221320Z"));
+        assert!(!criteria.is_exempt("feature"));
+    }
+
+    #[test]
+    fn merged() {
+        let protected = BTreeSet::new();
+        let base = vec!["m1".to_string(), "m2".to_string()];
+        let criteria = Criteria {
+            merged_into: Some(("main", &base)),
+            ..criteria(&protected)
+        };
+        assert!(criteria.matches(&candidate("empty", None, &[])));
+        assert!(criteria.matches(&candidate("behind", None, &["m1"])));
+        assert!(criteria.matches(&candidate("same", None, &["m1", "m2"])));
+        assert!(!criteria.matches(&candidate("ahead", None, &["m1", "m2", "m3"])));
+        assert!(!criteria.matches(&candidate("diverged", None, &["m1", "m3"])));
+    }
+
+    #[test]
+    fn age() {
+        let protected = BTreeSet::new();
+        let criteria = Criteria {
+            older_than: Some(7 * DAY),
+            ..criteria(&protected)
+        };
+        // age doesn't depend on migrations, which may be older than the branch
+        assert!(criteria.matches(&candidate("old", Some(10 * DAY), &[])));
+        assert!(criteria.matches(&candidate("week", Some(7 * DAY), &["m1"])));
+        assert!(!criteria.matches(&candidate("new", Some(DAY), &["m1"])));
+        assert!(!criteria.matches(&candidate("unknown", None, &[])));
+    }
+
+    #[test]
+    fn git_branch() {
+        let protected = BTreeSet::new();
+        let git_branches = BTreeSet::from(["feature".to_string()]);
+        let criteria = Criteria {
+            git_branches: Some(&git_branches),
+            ..criteria(&protected)
+        };
+        assert!(!criteria.matches(&candidate("feature", None, &[])));
+        assert!(criteria.matches(&candidate("gone", None, &[])));
+    }
+
+    #[test]
+    fn all_criteria() {
+        let protected = BTreeSet::new();
+        let base = vec!["m1".to_string()];
+        let git_branches = BTreeSet::from(["feature".to_string()]);
+        let criteria = Criteria {
+            merged_into: Some(("main", &base)),
+            older_than: Some(7 * DAY),
+            git_branches: Some(&git_branches),
+            ..criteria(&protected)
+        };
+        assert!(criteria.matches(&candidate("stale", Some(30 * DAY), &["m1"])));
+        assert!(!criteria.matches(&candidate("feature", Some(30 * DAY), &["m1"])));
+        assert!(!criteria.matches(&candidate("recent", Some(DAY), &["m1"])));
+        assert!(!criteria.matches(&candidate("unmerged", Some(30 * DAY), &["m2"])));
+    }
+}
@@ -25,6 +25,7 @@ pub async fn main(
     if options.target_branch == current_branch {
         anyhow::bail!("Cannot rebase the current branch on top of itself");
     }
+    project.check_protected_branch(&current_branch, options.force_protected)?;
 
     let temp_branch = clone_target_branch(&options.target_branch, source_connection).await?;
 
@@ -77,6 +78,10 @@ pub struct Command {
     /// Skip applying migrations generated from the rebase.
     #[arg(long)]
     pub no_apply: bool,
+
+    /// Proceed even if the branch is protected in the project manifest.
+    #[arg(long)]
+    pub force_protected: bool,
 }
 
 async fn rebase(
@@ -117,7 +122,7 @@ async fn rebase(
     anyhow::Ok(())
 }
 
-async fn rename_temp_to_source(
+pub(super) async fn rename_temp_to_source(
     temp_branch: &str,
     source_branch: &str,
     options: &Options,
@@ -144,8 +149,11 @@ async fn rename_temp_to_source(
     Ok(())
 }
 
-async fn clone_target_branch(branch: &str, connection: &mut Connection) -> anyhow::Result<String> {
-    eprintln!("Cloning target branch '{}' for rebase...", branch.success());
+pub(super) async fn clone_target_branch(
+    branch: &str,
+    connection: &mut Connection,
+) -> anyhow::Result<String> {
+    eprintln!("Cloning target branch '{}'...", branch.success());
 
     let temp_branch_name = Uuid::new_v4().to_string();
 
@@ -11,6 +11,9 @@ pub async fn run(
     connection: &mut Connection,
     cli_opts: &Options,
 ) -> anyhow::Result<branch::CommandResult> {
+    context
+        .check_protected(&options.old_name, options.force_protected)
+        .await?;
     let current_branch = context.get_current_branch(connection).await?;
 
     let rename_current = options.old_name == current_branch
@@ -74,4 +77,8 @@ pub struct Command {
     /// Close any existing connection to the branch before renaming it.
     #[arg(long)]
     pub force: bool,
+
+    /// Proceed even if the branch is protected in the project manifest.
+    #[arg(long)]
+    pub force_protected: bool,
 }
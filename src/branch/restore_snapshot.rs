@@ -0,0 +1,113 @@
+use edgeql_parser::helpers::quote_name;
+
+use crate::branch::connections::get_connection_to_modify;
+use crate::branch::context::Context;
+use crate::branch::snapshot;
+use crate::commands::{ExitCode, Options};
+use crate::connect::Connection;
+use crate::portable::exit_codes;
+use crate::{print, question};
+
+pub async fn main(
+    cmd: &Command,
+    context: &Context,
+    connection: &mut Connection,
+    options: &Options,
+) -> anyhow::Result<()> {
+    let snapshots = snapshot::list(connection, &cmd.branch).await?;
+
+    if cmd.list {
+        if snapshots.is_empty() {
+            eprintln!("No snapshots of '{}' found.", cmd.branch);
+        }
+        for name in snapshots.iter().rev() {
+            println!("{name}");
+        }
+        return Ok(());
+    }
+
+    let snapshot = match &cmd.snapshot {
+        Some(name) if snapshots.contains(name) => name.clone(),
+        Some(name) => anyhow::bail!("'{name}' is not a snapshot of '{}'", cmd.branch),
+        None => match snapshots.last() {
+            Some(name) => name.clone(),
+            None => anyhow::bail!("No snapshots of '{}' found", cmd.branch),
+        },
+    };
+
+    context
+        .check_protected(&cmd.branch, cmd.force_protected)
+        .await?;
+
+    if !cmd.non_interactive {
+        let q = question::Confirm::new_dangerous(format!(
+            "Do you really want to replace the branch {:?} with the snapshot {:?}?",
+            cmd.branch, snapshot
+        ));
+        if !connection.ping_while(q.async_ask()).await? {
+            print::error!("Canceled by user.");
+            return Err(ExitCode::new(exit_codes::NOT_CONFIRMED).into());
+        }
+    }
+
+    let mut modify = get_connection_to_modify(&cmd.branch, options, connection).await?;
+    let exists = modify
+        .connection
+        .query_required_single::<bool, _>(
+            "SELECT EXISTS (SELECT sys::Database FILTER .name = <str>$0)",
+            &(cmd.branch.as_str(),),
+        )
+        .await?;
+    if exists {
+        let (status, _warnings) = modify
+            .connection
+            .execute(
+                &format!("drop branch {} force", quote_name(&cmd.branch)),
+                &(),
+            )
+            .await?;
+        print::completion(status);
+    }
+    let (status, _warnings) = modify
+        .connection
+        .execute(
+            &format!(
+                "create data branch {} from {}",
+                quote_name(&cmd.branch),
+                quote_name(&snapshot)
+            ),
+            &(),
+        )
+        .await?;
+    print::completion(status);
+    modify.clean().await?;
+
+    eprintln!("Restored '{}' from '{snapshot}'", cmd.branch);
+    Ok(())
+}
+
+/// Restores a branch from a snapshot taken before it was wiped or dropped.
+///
+/// Snapshots are kept as branches named `snapshot--<branch>--<timestamp>`,
+/// only the last few of them are kept for every branch.
+#[derive(clap::Args, Debug, Clone)]
+pub struct Command {
+    /// The branch to restore.
+    pub branch: String,
+
+    /// The snapshot to restore from (the latest one by default).
+    #[arg(long)]
+    pub snapshot: Option<String>,
+
+    /// List snapshots of the branch instead of restoring it, newest first.
+    #[arg(long, conflicts_with = "snapshot")]
+    pub list: bool,
+
+    /// Restore without asking for confirmation.
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Proceed even if the branch is protected in the project manifest.
+    #[arg(long)]
+    pub force_protected: bool,
+}
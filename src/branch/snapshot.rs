@@ -0,0 +1,180 @@
+use std::time::SystemTime;
+
+use edgeql_parser::helpers::quote_name;
+
+use crate::branch::connections::get_connection_to_modify;
+use crate::branding::BRANDING_CLI_CMD;
+use crate::cli::env::{AutoBackupMode, Env};
+use crate::commands::Options;
+use crate::connect::Connection;
+use crate::print::{self, Highlight};
+
+const PREFIX: &str = "snapshot--";
+const SEPARATOR: &str = "--";
+
+/// Number of snapshots kept per branch unless `GEL_BRANCH_SNAPSHOTS_KEEP` is
+/// set, older ones are dropped when a new snapshot is taken
+const DEFAULT_KEEP_SNAPSHOTS: usize = 3;
+
+fn snapshot_name(branch: &str, time: SystemTime) -> String {
+    let timestamp = humantime::format_rfc3339_seconds(time)
+        .to_string()
+        .replace(['-', ':'], "");
+    format!("{PREFIX}{branch}{SEPARATOR}{timestamp}")
+}
+
+/// Returns the branch name and the timestamp of a snapshot branch
+pub fn parse_snapshot_name(name: &str) -> Option<(&str, &str)> {
+    name.strip_prefix(PREFIX)?.rsplit_once(SEPARATOR)
+}
+
+pub fn is_snapshot(name: &str) -> bool {
+    parse_snapshot_name(name).is_some()
+}
+
+/// Number of snapshots to keep per branch, at least one
+pub fn keep_snapshots() -> anyhow::Result<usize> {
+    Ok(Env::branch_snapshots_keep()?
+        .unwrap_or(DEFAULT_KEEP_SNAPSHOTS)
+        .max(1))
+}
+
+/// Returns snapshots of the branch, oldest first
+pub async fn list(connection: &mut Connection, branch: &str) -> anyhow::Result<Vec<String>> {
+    let branches: Vec<String> = connection
+        .query(
+            "SELECT (SELECT sys::Database FILTER NOT .builtin).name",
+            &(),
+        )
+        .await?;
+    let mut snapshots = branches
+        .into_iter()
+        .filter(|name| matches!(parse_snapshot_name(name), Some((b, _)) if b == branch))
+        .collect::<Vec<_>>();
+    // timestamps have a fixed width, so names sort chronologically
+    snapshots.sort();
+    Ok(snapshots)
+}
+
+/// Copies the branch (with data) to a timestamped snapshot branch before a
+/// destructive operation
+///
+/// Copying a branch requires that there are no other connections to it, so
+/// if `connection` is connected to the branch itself, it is closed while the
+/// copy is made and then reopened (keeping the session state).
+///
+/// Like automatic backups before migrations, this is skipped in CI and
+/// when `GEL_AUTO_BACKUP_MODE=disabled`. Snapshots themselves are never
+/// snapshotted.
+pub async fn take(
+    branch: &str,
+    options: &Options,
+    connection: &mut Connection,
+) -> anyhow::Result<Option<String>> {
+    let disabled = matches!(Env::auto_backup_mode()?, Some(AutoBackupMode::Disabled));
+    if disabled || *Env::in_ci()?.unwrap_or_default() {
+        log::info!("Skipping branch snapshot, disabled by environment");
+        return Ok(None);
+    }
+    if is_snapshot(branch) {
+        log::info!("Skipping snapshot of snapshot branch {branch:?}");
+        return Ok(None);
+    }
+
+    let connected = connection.get_current_branch().await? == branch;
+    let mut modify = get_connection_to_modify(branch, options, connection).await?;
+    let result = if connected {
+        let mut connector = options.conn_params.clone();
+        let other = modify.connection.get_current_branch().await?.to_string();
+        let state = connection.get_state().clone();
+        let own = std::mem::replace(connection, connector.branch(&other)?.connect().await?);
+        own.terminate().await?;
+        let result = copy(branch, &mut modify.connection).await;
+        let mut own = connector.branch(branch)?.connect().await?;
+        own.set_state(state);
+        *connection = own;
+        result
+    } else {
+        copy(branch, &mut modify.connection).await
+    };
+    modify.clean().await?;
+    let name = result?;
+
+    eprintln!(
+        "Snapshot saved as '{}', restore it with `{BRANDING_CLI_CMD} branch restore-snapshot {}`",
+        name.emphasized(),
+        branch
+    );
+    Ok(Some(name))
+}
+
+async fn copy(branch: &str, connection: &mut Connection) -> anyhow::Result<String> {
+    let keep = keep_snapshots()?;
+    let snapshots = list(connection, branch).await?;
+    let name = unique_name(snapshot_name(branch, SystemTime::now()), &snapshots);
+    eprintln!("Saving a snapshot of '{branch}'...");
+    let (status, _warnings) = connection
+        .execute(
+            &format!(
+                "create data branch {} from {}",
+                quote_name(&name),
+                quote_name(branch)
+            ),
+            &(),
+        )
+        .await?;
+    print::completion(status);
+
+    let snapshots = list(connection, branch).await?;
+    let excess = snapshots.len().saturating_sub(keep);
+    for old in &snapshots[..excess] {
+        let (status, _warnings) = connection
+            .execute(&format!("drop branch {} force", quote_name(old)), &())
+            .await?;
+        print::completion(status);
+    }
+    Ok(name)
+}
+
+/// Timestamps have one second resolution, so snapshots taken within the same
+/// second get a numeric suffix
+fn unique_name(name: String, existing: &[String]) -> String {
+    if !existing.contains(&name) {
+        return name;
+    }
+    (2..)
+        .map(|n| format!("{name}-{n}"))
+        .find(|candidate| !existing.contains(candidate))
+        .expect("there is a free suffix")
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use super::{parse_snapshot_name, snapshot_name, unique_name};
+
+    #[test]
+    fn names() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let name = snapshot_name("feature--x", time);
+        assert_eq!(name, "snapshot--feature--x--20231114T221320Z");
+        assert_eq!(
+            parse_snapshot_name(&name),
+            Some(("feature--x", "20231114T221320Z"))
+        );
+        assert_eq!(parse_snapshot_name("feature"), None);
+    }
+
+    #[test]
+    fn collisions() {
+        let name = String::from("snapshot--main--20231114T221320Z");
+        assert_eq!(unique_name(name.clone(), &[]), name);
+        let existing = vec![name.clone(), format!("{name}-2")];
+        assert_eq!(unique_name(name.clone(), &existing), format!("{name}-3"));
+        assert_eq!(
+            parse_snapshot_name(&format!("{name}-3")),
+            Some(("main", "20231114T221320Z-3"))
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use crate::branch::connections::connect_if_branch_exists;
+use crate::branch::context::Context;
+use crate::branch::switch;
+use crate::branding::MANIFEST_FILE_DISPLAY_NAME;
+use crate::connect::Connector;
+use crate::{branch, git, print};
+
+pub async fn run(
+    options: &Command,
+    context: &Context,
+    connector: &mut Connector,
+) -> anyhow::Result<branch::CommandResult> {
+    let Some(project) = context.get_project().await? else {
+        anyhow::bail!("`branch sync-git` must be used within a project");
+    };
+    let Some(git_branch) = git::git_current_branch().await else {
+        anyhow::bail!(
+            "Cannot determine the current git branch (not a git repository or detached HEAD)"
+        );
+    };
+    let Some(target_branch) = project.manifest.project().branch_for_git(&git_branch) else {
+        anyhow::bail!(
+            "Syncing branches with git is not enabled. \
+             Set `git-branch-sync = true` in the [project] section of {MANIFEST_FILE_DISPLAY_NAME}"
+        );
+    };
+
+    if let Some(mut connection) = connect_if_branch_exists(connector).await? {
+        if context.get_current_branch(&mut connection).await? == target_branch {
+            print::msg!("Already on '{target_branch}'");
+            return Ok(branch::CommandResult::default());
+        }
+    }
+
+    switch::run(
+        &switch::Command {
+            target_branch,
+            create: true,
+            empty: options.empty,
+            from: options.from.clone(),
+            copy_data: options.copy_data,
+        },
+        context,
+        connector,
+    )
+    .await
+}
+
+/// Switch to the branch that matches the current git branch, creating it
+/// if it doesn't exist.
+///
+/// Branch names are taken from the `git-branch-map` table in the [project]
+/// section of the manifest, defaulting to the name of the git branch.
+#[derive(clap::Args, Debug, Clone)]
+pub struct Command {
+    /// If creating a new branch: whether the new branch should be empty.
+    #[arg(short = 'e', long, conflicts_with = "copy_data")]
+    pub empty: bool,
+
+    /// If creating a new branch: the optional 'base' of the branch to create.
+    #[arg(long)]
+    pub from: Option<String>,
+
+    /// If creating a new branch: whether to copy data from the 'base' branch.
+    #[arg(alias = "cp", long)]
+    pub copy_data: bool,
+}
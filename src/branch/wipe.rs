@@ -1,6 +1,7 @@
 use crate::branch::connections::connect_if_branch_exists;
 use crate::branch::context::Context;
-use crate::commands::ExitCode;
+use crate::branch::snapshot;
+use crate::commands::{ExitCode, Options};
 use crate::connect::Connector;
 use crate::portable::exit_codes;
 use crate::{hooks, print, question};
@@ -9,7 +10,12 @@ pub async fn main(
     cmd: &Command,
     context: &Context,
     connector: &mut Connector,
+    options: &Options,
 ) -> anyhow::Result<()> {
+    context
+        .check_protected(&cmd.target_branch, cmd.force_protected)
+        .await?;
+
     let connection = connect_if_branch_exists(connector.branch(&cmd.target_branch)?).await?;
 
     if connection.is_none() {
@@ -30,6 +36,10 @@ pub async fn main(
         }
     }
 
+    if !cmd.no_snapshot {
+        snapshot::take(&cmd.target_branch, options, &mut connection).await?;
+    }
+
     do_wipe(&mut connection, context).await?;
     Ok(())
 }
@@ -66,4 +76,12 @@ pub struct Command {
     /// Wipe without asking for confirmation.
     #[arg(long)]
     pub non_interactive: bool,
+
+    /// Do not save a snapshot of the branch before wiping it.
+    #[arg(long)]
+    pub no_snapshot: bool,
+
+    /// Proceed even if the branch is protected in the project manifest.
+    #[arg(long)]
+    pub force_protected: bool,
 }
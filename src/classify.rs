@@ -11,3 +11,16 @@ pub fn is_analyze(query: &str) -> bool {
         None => false,                 // but should be unreachable
     }
 }
+
+/// Checks the command status of a completed statement for schema changes
+pub fn is_schema_change(status: &str) -> bool {
+    const PREFIXES: &[&str] = &[
+        "CREATE",
+        "ALTER",
+        "DROP",
+        "COMMIT MIGRATION",
+        "RESET SCHEMA",
+        "ROLLBACK",
+    ];
+    PREFIXES.iter().any(|p| status.starts_with(p))
+}
@@ -205,6 +205,10 @@ define_env! {
     #[env(GEL_AUTO_BACKUP_MODE)]
     auto_backup_mode: AutoBackupMode,
 
+    /// Number of snapshots kept per branch
+    #[env(GEL_BRANCH_SNAPSHOTS_KEEP)]
+    branch_snapshots_keep: usize,
+
     /// How we should detect uv project
     #[env(GEL_GENERATE_USE_UV)]
     use_uv: UseUv,
@@ -535,6 +535,7 @@ fn try_project_init(opts: Option<&crate::options::Options>) -> anyhow::Result<In
             link: false,
             server_start_conf: None,
             cloud_opts: cloud_options.clone(),
+            git_hook: false,
         };
         let opts = if let Some(opts) = opts {
             crate::options::Options {
@@ -2,6 +2,7 @@ use std::borrow::Cow;
 use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr;
 
+use anyhow::Context;
 use clap::{CommandFactory, FromArgMatches};
 use const_format::concatcp;
 use gel_tokio::dsn::DatabaseBranch;
@@ -18,18 +19,28 @@ use crate::branding::BRANDING;
 use crate::commands::Options;
 use crate::commands::execute;
 use crate::commands::parser::{Backslash, BackslashCmd, Setting, StateParam};
-use crate::print;
 use crate::print::style::Styler;
+use crate::print::{self, msg};
 use crate::prompt;
 use crate::repl;
 use crate::table;
 
 pub static CMD_CACHE: LazyLock<CommandCache> = LazyLock::new(CommandCache::new);
 
+pub const DEFAULT_WATCH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
 pub enum ExecuteResult {
     Skip,
     Quit,
     Input(String),
+    WatchQuery {
+        query: String,
+        interval: std::time::Duration,
+    },
+    Script {
+        name: String,
+        text: String,
+    },
 }
 
 const HELP: &str = concatcp!(
@@ -46,6 +57,7 @@ Introspection
 
   \d [-v] NAME              Describe schema object
   \ds                       Describe whole schema   (alias: \describe schema)
+  \ds --graph               Schema diagram in Mermaid syntax
   \l                        List databases/branches (alias: \list branches)
   \ls [-sc]  [PATTERN]      List scalar types       (alias: \list scalars)
   \lt [-sc]  [PATTERN]      List object types       (alias: \list types)
@@ -54,12 +66,25 @@ Introspection
   \la [-vsc] [PATTERN]      List expression aliases (alias: \list aliases)
   \lc [-c]   [PATTERN]      List casts              (alias: \list casts)
   \li [-vsc] [PATTERN]      List indexes            (alias: \list indexes)
+  \lf [-vsc] [PATTERN]      List functions          (alias: \list functions)
+  \lo [-vsc] [PATTERN]      List operators          (alias: \list operators)
+  \lg [-vsc] [PATTERN]      List globals and values (alias: \list globals)
+  \lp [-vsc] [TYPE]         List access policies    (alias: \list policies)
+  \ltr [-vsc] [TYPE]        List triggers           (alias: \list triggers)
+  \lrw [-sc] [TYPE]         List mutation rewrites  (alias: \list rewrites)
 
 Operations
   \dump FILENAME            Create dump of current database as a file
   \restore FILENAME         Restore database from file into current database
+  \import --type TYPE FILE  Insert objects from a CSV or JSON Lines file
   \expand                   Print expanded output of last `analyze` operation
   \E, \last-error           More information on most recent error
+  \watch [INTERVAL] [QUERY] Re-run the last query (or QUERY) every INTERVAL
+                            seconds (default 2) until Ctrl+C
+  \o [-ta] FILE             Write query results to FILE (-t: also print
+                            them, -a: append)
+  \o                        Print query results to the terminal again
+  \i, \include FILE         Execute queries from FILE in current session
 
 Editing
   \s, \history              Show history
@@ -336,8 +361,16 @@ impl CommandCache {
         aliases.insert("la", &["list", "aliases"]);
         aliases.insert("lc", &["list", "casts"]);
         aliases.insert("li", &["list", "indexes"]);
+        aliases.insert("lf", &["list", "functions"]);
+        aliases.insert("lo", &["list", "operators"]);
+        aliases.insert("lg", &["list", "globals"]);
+        aliases.insert("lp", &["list", "policies"]);
+        aliases.insert("ltr", &["list", "triggers"]);
+        aliases.insert("lrw", &["list", "rewrites"]);
         aliases.insert("s", &["history"]);
         aliases.insert("e", &["edit"]);
+        aliases.insert("o", &["output"]);
+        aliases.insert("i", &["include"]);
         aliases.insert("c", &["connect"]);
         aliases.insert("E", &["last-error"]);
         aliases.insert("q", &["exit"]);
@@ -548,6 +581,7 @@ pub fn get_setting(s: &Setting, prompt: &repl::State) -> Cow<'static, str> {
         DisplayTypenames(_) => bool_str(prompt.display_typenames).into(),
         ExpandStrings(_) => bool_str(prompt.print.expand_strings).into(),
         PrintStats(_) => prompt.print_stats.as_str().into(),
+        CsvFlatten(_) => prompt.csv_flatten.as_str().into(),
     }
 }
 
@@ -596,6 +630,8 @@ pub async fn execute(
         Common(cmd) => {
             prompt.soft_reconnect().await?;
             let conn = prompt.connection.as_mut().expect("connection established");
+            // migrations, restores and branch commands may change the schema
+            prompt.schema_outdated = true;
             let result = Box::pin(execute::common(Some(conn), cmd, &options)).await?;
 
             if let Some(branch) = result.new_branch {
@@ -665,6 +701,9 @@ pub async fn execute(
                 PrintStats(v) => {
                     prompt.print_stats = v.value.expect("only writes here");
                 }
+                CsvFlatten(v) => {
+                    prompt.csv_flatten = v.value.expect("only writes here");
+                }
             }
             Ok(Skip)
         }
@@ -739,6 +778,49 @@ pub async fn execute(
             prompt.show_history().await?;
             Ok(Skip)
         }
+        Watch(c) => {
+            let (interval, query) = c.interval_and_query()?;
+            let Some(query) = query.or_else(|| prompt.last_query.clone()) else {
+                anyhow::bail!("no query to watch: run a query first or pass it as an argument");
+            };
+            Ok(WatchQuery {
+                query,
+                interval: interval.unwrap_or(DEFAULT_WATCH_INTERVAL),
+            })
+        }
+        Output(c) => {
+            if let Some(path) = &c.file {
+                let file = tokio::fs::OpenOptions::new()
+                    .write(true)
+                    .create(true)
+                    .append(c.append)
+                    .truncate(!c.append)
+                    .open(path)
+                    .await
+                    .with_context(|| format!("cannot open {path:?}"))?;
+                prompt.output_redirect = Some(repl::OutputRedirect {
+                    path: path.clone(),
+                    file,
+                    tee: c.tee,
+                });
+                msg!("Writing query results to {}", path.display());
+            } else if let Some(redirect) = prompt.output_redirect.take() {
+                msg!(
+                    "Stopped writing query results to {}",
+                    redirect.path.display()
+                );
+            }
+            Ok(Skip)
+        }
+        Include(c) => {
+            let text = tokio::fs::read_to_string(&c.file)
+                .await
+                .with_context(|| format!("cannot read {:?}", c.file))?;
+            Ok(Script {
+                name: c.file.display().to_string(),
+                text,
+            })
+        }
         Edit(c) => match prompt.spawn_editor(c.entry).await? {
             prompt::Input::Text(text) => Ok(Input(text)),
             prompt::Input::Interrupt | prompt::Input::Eof => Ok(Skip),
@@ -30,6 +30,14 @@ pub fn main(options: &Options) -> Result<(), anyhow::Error> {
                     subcommand: M::Edit(cmd),
                     ..
                 }) if cmd.no_check => migrations::edit_no_check(cmd, &opts),
+                Some(Migration {
+                    subcommand: M::Lint(cmd),
+                    ..
+                }) => migrations::lint_no_conn(cmd, &opts),
+                Some(Migration {
+                    subcommand: M::Verify(cmd),
+                    ..
+                }) => migrations::verify_no_conn(cmd, &opts),
                 Some(Migration {
                     subcommand: M::UpgradeCheck(params),
                     ..
@@ -60,7 +60,7 @@ pub async fn drop(
 pub async fn wipe(
     connection: &mut Connection,
     cmd: &WipeDatabase,
-    skip_hooks: bool,
+    options: &Options,
 ) -> Result<(), anyhow::Error> {
     if connection.get_version().await?.specific().major >= 5 {
         print::warn!("'database wipe' is deprecated in {BRANDING} 5+. Please use 'branch wipe'");
@@ -73,6 +73,17 @@ pub async fn wipe(
         ))
         .hint("Use `database drop`, `database create`")?;
     }
+    let context = crate::branch::context::Context::new(
+        cmd.instance_opts.maybe_instance().as_ref(),
+        options.skip_hooks,
+        false,
+    )
+    .await?;
+    let branch = context.get_current_branch(connection).await?;
+    context
+        .check_protected(&branch, cmd.force_protected)
+        .await?;
+
     if !cmd.non_interactive {
         let q = question::Confirm::new_dangerous(format!(
             "Do you really want to wipe \
@@ -85,12 +96,9 @@ pub async fn wipe(
         }
     }
 
-    let context = crate::branch::context::Context::new(
-        cmd.instance_opts.maybe_instance().as_ref(),
-        skip_hooks,
-        false,
-    )
-    .await?;
+    if !cmd.no_snapshot {
+        crate::branch::snapshot::take(&branch, options, connection).await?;
+    }
     crate::branch::wipe::do_wipe(connection, &context).await?;
     Ok(())
 }
@@ -1,8 +1,55 @@
+use std::path::Path;
+
+use anyhow::Context;
+
 use crate::commands::Options;
+use crate::commands::parser::{DescribeSchema, SchemaFormat};
+use crate::commands::{schema_graph, schema_json};
 use crate::connect::Connection;
 use crate::highlight;
+use crate::print::msg;
 
-pub async fn describe_schema(cli: &mut Connection, options: &Options) -> Result<(), anyhow::Error> {
+pub async fn describe_schema(
+    cli: &mut Connection,
+    options: &Options,
+    params: &DescribeSchema,
+) -> Result<(), anyhow::Error> {
+    let format = if params.graph {
+        SchemaFormat::Mermaid
+    } else {
+        params.format
+    };
+    if params.output_dir.is_some() && format != SchemaFormat::Json {
+        anyhow::bail!("`--output-dir` requires `--format=json`");
+    }
+    match format {
+        SchemaFormat::Sdl => {
+            if !params.module.is_empty() {
+                anyhow::bail!("`--module` is not supported for `--format=sdl`");
+            }
+        }
+        SchemaFormat::Json => {
+            let document = schema_json::fetch(cli, &params.module).await?;
+            if let Some(dir) = &params.output_dir {
+                write_modules(dir, document).await?;
+            } else {
+                println!("{}", serde_json::to_string_pretty(&document)?);
+            }
+            return Ok(());
+        }
+        SchemaFormat::Dot | SchemaFormat::Mermaid => {
+            let mut graph = schema_graph::fetch(cli).await?;
+            if !params.module.is_empty() {
+                graph.retain_modules(&params.module);
+            }
+            if format == SchemaFormat::Dot {
+                print!("{}", graph.to_dot());
+            } else {
+                print!("{}", graph.to_mermaid());
+            }
+            return Ok(());
+        }
+    }
     let text = cli
         .query_required_single::<String, ()>("DESCRIBE SCHEMA AS SDL", &())
         .await?;
@@ -15,3 +62,26 @@ pub async fn describe_schema(cli: &mut Connection, options: &Options) -> Result<
     }
     Ok(())
 }
+
+/// Writes `<module>.json` for each module, with the same structure as the
+/// full document
+async fn write_modules(dir: &Path, document: schema_json::Document) -> anyhow::Result<()> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("cannot create {dir:?}"))?;
+    let count = document.modules.len();
+    for module in document.modules {
+        let path = dir.join(urlencoding::encode(&module.name).into_owned() + ".json");
+        let single = schema_json::Document {
+            version: document.version,
+            modules: vec![module],
+        };
+        let mut data = serde_json::to_string_pretty(&single)?;
+        data.push('\n');
+        tokio::fs::write(&path, data)
+            .await
+            .with_context(|| format!("cannot write {path:?}"))?;
+    }
+    msg!("Wrote {count} module files to {}", dir.display());
+    Ok(())
+}
@@ -50,6 +50,65 @@ pub async fn common(
             ListCmd::Casts(c) => {
                 commands::list_casts(conn, options, &c.pattern, c.case_sensitive).await?;
             }
+            ListCmd::Functions(c) => {
+                commands::list_functions(
+                    conn,
+                    options,
+                    &c.pattern,
+                    c.system,
+                    c.case_sensitive,
+                    c.verbose,
+                )
+                .await?;
+            }
+            ListCmd::Operators(c) => {
+                commands::list_operators(
+                    conn,
+                    options,
+                    &c.pattern,
+                    c.system,
+                    c.case_sensitive,
+                    c.verbose,
+                )
+                .await?;
+            }
+            ListCmd::Globals(c) => {
+                commands::list_globals(
+                    conn,
+                    options,
+                    &c.pattern,
+                    c.system,
+                    c.case_sensitive,
+                    c.verbose,
+                )
+                .await?;
+            }
+            ListCmd::Policies(c) => {
+                commands::list_policies(
+                    conn,
+                    options,
+                    &c.pattern,
+                    c.system,
+                    c.case_sensitive,
+                    c.verbose,
+                )
+                .await?;
+            }
+            ListCmd::Triggers(c) => {
+                commands::list_triggers(
+                    conn,
+                    options,
+                    &c.pattern,
+                    c.system,
+                    c.case_sensitive,
+                    c.verbose,
+                )
+                .await?;
+            }
+            ListCmd::Rewrites(c) => {
+                commands::list_rewrites(conn, options, &c.pattern, c.system, c.case_sensitive)
+                    .await?;
+            }
             ListCmd::Indexes(c) => {
                 commands::list_indexes(
                     conn,
@@ -113,8 +172,8 @@ pub async fn common(
             DescribeCmd::Object(c) => {
                 commands::describe(conn, options, &c.name, c.verbose).await?;
             }
-            DescribeCmd::Schema(_) => {
-                commands::describe_schema(conn, options).await?;
+            DescribeCmd::Schema(c) => {
+                commands::describe_schema(conn, options, c).await?;
             }
         },
         Dump(c) => {
@@ -123,6 +182,9 @@ pub async fn common(
         Restore(params) => {
             commands::restore(conn, options, params).await?;
         }
+        Import(params) => {
+            commands::import(conn, options, params).await?;
+        }
         Configure(cmd) => {
             commands::configure::run(cmd, conn, options).await?;
         }
@@ -134,7 +196,7 @@ pub async fn common(
                 commands::database::drop(conn, d, options).await?;
             }
             DatabaseCmd::Wipe(w) => {
-                commands::database::wipe(conn, w, options.skip_hooks).await?;
+                commands::database::wipe(conn, w, options).await?;
             }
         },
         Branch(_) => unreachable!(),
@@ -154,6 +216,18 @@ pub async fn common(
             MigrationCmd::Log(cmd) => {
                 migrations::log(conn, cmd, options).await?;
             }
+            MigrationCmd::Diff(cmd) => {
+                migrations::diff(conn, cmd, options).await?;
+            }
+            MigrationCmd::Lint(cmd) => {
+                migrations::lint(cmd, options).await?;
+            }
+            MigrationCmd::Verify(cmd) => {
+                migrations::verify(cmd, options).await?;
+            }
+            MigrationCmd::Revert(cmd) => {
+                migrations::revert(conn, cmd, options).await?;
+            }
             MigrationCmd::Edit(cmd) => {
                 migrations::edit(conn, cmd, options).await?;
             }
@@ -0,0 +1,449 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Context;
+use indicatif::ProgressBar;
+
+use gel_derive::Queryable;
+use gel_protocol::client_message::{Cardinality, CompilationOptions};
+use gel_protocol::codec::ObjectShape;
+use gel_protocol::common::{Capabilities, InputLanguage, IoFormat};
+use gel_protocol::server_message::TransactionState;
+use gel_protocol::value::Value;
+
+use crate::commands::Options;
+use crate::commands::helpers::{quote_name, quote_namespaced};
+use crate::commands::parser::{Import, ImportFormat};
+use crate::connect::Connection;
+use crate::print::{self, msg};
+use crate::prompt::variable::{self, VariableInput};
+use crate::variables::input_types;
+
+#[derive(Queryable)]
+struct Property {
+    name: String,
+    target_name: String,
+    multi: bool,
+}
+
+struct Record {
+    line: u64,
+    raw: Raw,
+    /// Text of each column, `None` for empty cells and missing keys
+    values: anyhow::Result<Vec<Option<String>>>,
+}
+
+enum Raw {
+    Csv(csv::ByteRecord),
+    Json(String),
+}
+
+enum Input {
+    Csv {
+        records: csv::ByteRecordsIntoIter<fs::File>,
+        width: usize,
+    },
+    Json {
+        lines: io::Lines<BufReader<fs::File>>,
+        line: u64,
+        columns: Vec<String>,
+        first: Option<String>,
+    },
+}
+
+enum RejectWriter {
+    Csv(csv::Writer<fs::File>),
+    Json(io::BufWriter<fs::File>),
+}
+
+struct Rejects {
+    writer: Option<RejectWriter>,
+    count: u64,
+}
+
+pub async fn import(
+    cli: &mut Connection,
+    _options: &Options,
+    params: &Import,
+) -> Result<(), anyhow::Error> {
+    if !matches!(cli.transaction_state(), TransactionState::NotInTransaction) {
+        anyhow::bail!("import cannot be run inside a transaction");
+    }
+    let format = match params.format {
+        Some(format) => format,
+        None => guess_format(&params.file)?,
+    };
+    let (mut input, columns) = Input::open(&params.file, format)?;
+    if columns.is_empty() {
+        anyhow::bail!("{:?} contains no columns", params.file);
+    }
+
+    let type_name: Option<String> = cli
+        .query_single(
+            &format!(
+                "SELECT (INTROSPECT {})[IS schema::ObjectType].name",
+                quote_namespaced(&params.type_name)
+            ),
+            &(),
+        )
+        .await?
+        .0;
+    let Some(type_name) = type_name else {
+        anyhow::bail!("{} is not an object type", params.type_name);
+    };
+    let properties = cli
+        .query::<Property, _>(
+            r###"
+            SELECT (
+                SELECT schema::ObjectType FILTER .name = <str>$0
+            ).properties {
+                name,
+                target_name := .target.name,
+                multi := .cardinality = schema::Cardinality.Many,
+            }
+            "###,
+            &(type_name.as_str(),),
+        )
+        .await?;
+    let statement = insert_statement(&type_name, &columns, &properties)?;
+
+    let flags = CompilationOptions {
+        implicit_limit: None,
+        implicit_typenames: false,
+        implicit_typeids: false,
+        explicit_objectids: true,
+        allow_capabilities: Capabilities::MODIFICATIONS,
+        input_language: InputLanguage::EdgeQL,
+        io_format: IoFormat::Binary,
+        expected_cardinality: Cardinality::One,
+    };
+    let description = cli.parse(&flags, &statement).await?;
+    let (shape, types) = input_types(&description.input()?)?;
+    let types = types
+        .into_iter()
+        .map(|(name, var_type)| {
+            let index = name.parse::<usize>().expect("positional parameters");
+            (index, var_type)
+        })
+        .collect::<Vec<_>>();
+
+    let mut rejects = Rejects::new(params.reject_file.as_deref(), format, &columns)?;
+    let bar = ProgressBar::new_spinner();
+    let mut imported = 0;
+    let mut batch = Vec::with_capacity(params.batch_size as usize);
+    while let Some(record) = input.next_record()? {
+        let value = match &record.values {
+            Ok(values) => row_value(values, &columns, &shape, &types),
+            Err(e) => Err(anyhow::anyhow!("{e:#}")),
+        };
+        match value {
+            Ok(value) => batch.push((record, value)),
+            Err(e) => bar.suspend(|| rejects.add(&record, &e))?,
+        }
+        if batch.len() as u64 >= params.batch_size {
+            imported += insert_batch(cli, &statement, &mut batch, &mut rejects, &bar).await?;
+            bar.set_message(format!("Imported {imported} rows into {type_name}"));
+            bar.tick();
+        }
+    }
+    imported += insert_batch(cli, &statement, &mut batch, &mut rejects, &bar).await?;
+    rejects.finish()?;
+    bar.finish_and_clear();
+
+    if rejects.count > 0 {
+        msg!(
+            "Imported {imported} rows into {type_name}, {} rows rejected{}",
+            rejects.count,
+            match &params.reject_file {
+                Some(path) => format!(" (written to {})", path.display()),
+                None => String::new(),
+            }
+        );
+    } else {
+        msg!("Imported {imported} rows into {type_name}");
+    }
+    Ok(())
+}
+
+fn guess_format(path: &Path) -> anyhow::Result<ImportFormat> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("csv") => Ok(ImportFormat::Csv),
+        Some("jsonl" | "ndjson") => Ok(ImportFormat::Jsonl),
+        _ => anyhow::bail!("cannot determine format of {path:?}, specify `--format`"),
+    }
+}
+
+fn insert_statement(
+    type_name: &str,
+    columns: &[String],
+    properties: &[Property],
+) -> anyhow::Result<String> {
+    let mut elements = Vec::with_capacity(columns.len());
+    for (index, column) in columns.iter().enumerate() {
+        let Some(prop) = properties.iter().find(|p| &p.name == column) else {
+            anyhow::bail!("column {column:?} is not a property of {type_name}");
+        };
+        if prop.multi {
+            anyhow::bail!("cannot import column {column:?}: multi properties are not supported");
+        }
+        elements.push(format!(
+            "{} := <optional {}>${index}",
+            quote_name(&prop.name),
+            quote_namespaced(&prop.target_name),
+        ));
+    }
+    Ok(format!(
+        "INSERT {} {{ {} }}",
+        quote_namespaced(type_name),
+        elements.join(", ")
+    ))
+}
+
+fn row_value(
+    values: &[Option<String>],
+    columns: &[String],
+    shape: &ObjectShape,
+    types: &[(usize, Arc<dyn VariableInput>)],
+) -> anyhow::Result<Value> {
+    let fields = types
+        .iter()
+        .map(|(index, var_type)| {
+            values[*index]
+                .as_deref()
+                .map(|text| {
+                    variable::parse_value(&**var_type, text).with_context(|| {
+                        format!(
+                            "invalid value of {:?} ({})",
+                            columns[*index],
+                            var_type.type_name()
+                        )
+                    })
+                })
+                .transpose()
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok(Value::Object {
+        shape: shape.clone(),
+        fields,
+    })
+}
+
+async fn insert_batch(
+    cli: &mut Connection,
+    statement: &str,
+    batch: &mut Vec<(Record, Value)>,
+    rejects: &mut Rejects,
+    bar: &ProgressBar,
+) -> anyhow::Result<u64> {
+    if batch.is_empty() {
+        return Ok(0);
+    }
+    cli.execute("START TRANSACTION", &()).await?;
+    let mut failed = false;
+    for (_, value) in batch.iter() {
+        if cli.execute(statement, value).await.is_err() {
+            failed = true;
+            break;
+        }
+    }
+    if !failed {
+        cli.execute("COMMIT", &()).await?;
+        let count = batch.len() as u64;
+        batch.clear();
+        return Ok(count);
+    }
+    cli.execute("ROLLBACK", &()).await?;
+
+    // Find out which rows are wrong by inserting them one by one. Each row
+    // gets its own implicit transaction, so the good rows of a failed batch
+    // are committed separately. This is slower, but a failed row doesn't
+    // abort the rest of the batch.
+    let mut count = 0;
+    for (record, value) in batch.drain(..) {
+        match cli.execute(statement, &value).await {
+            Ok(_) => count += 1,
+            Err(e) if cli.is_consistent() => bar.suspend(|| rejects.add(&record, &e.into()))?,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(count)
+}
+
+impl Input {
+    fn open(path: &Path, format: ImportFormat) -> anyhow::Result<(Input, Vec<String>)> {
+        let file = fs::File::open(path).with_context(|| format!("cannot open {path:?}"))?;
+        match format {
+            ImportFormat::Csv => {
+                // rows with a wrong number of fields are rejected one by one
+                let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(file);
+                let columns = reader
+                    .headers()
+                    .with_context(|| format!("cannot read header of {path:?}"))?
+                    .iter()
+                    .map(|name| name.trim().to_string())
+                    .collect::<Vec<_>>();
+                Ok((
+                    Input::Csv {
+                        records: reader.into_byte_records(),
+                        width: columns.len(),
+                    },
+                    columns,
+                ))
+            }
+            ImportFormat::Jsonl => {
+                let mut lines = BufReader::new(file).lines();
+                let mut line = 0;
+                // column names are taken from the first object
+                let first = loop {
+                    let Some(text) = lines.next().transpose()? else {
+                        anyhow::bail!("{path:?} is empty");
+                    };
+                    line += 1;
+                    if !text.trim().is_empty() {
+                        break text;
+                    }
+                };
+                let columns =
+                    serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&first)
+                        .with_context(|| format!("line {line} of {path:?} is not a JSON object"))?
+                        .keys()
+                        .cloned()
+                        .collect::<Vec<_>>();
+                Ok((
+                    Input::Json {
+                        lines,
+                        line: line - 1,
+                        columns: columns.clone(),
+                        first: Some(first),
+                    },
+                    columns,
+                ))
+            }
+        }
+    }
+
+    fn next_record(&mut self) -> anyhow::Result<Option<Record>> {
+        match self {
+            Input::Csv { records, width } => {
+                let Some(record) = records.next().transpose()? else {
+                    return Ok(None);
+                };
+                let values = csv_values(&record, *width);
+                Ok(Some(Record {
+                    line: record.position().map(|p| p.line()).unwrap_or(0),
+                    raw: Raw::Csv(record),
+                    values,
+                }))
+            }
+            Input::Json {
+                lines,
+                line,
+                columns,
+                first,
+            } => loop {
+                let text = match first.take() {
+                    Some(text) => text,
+                    None => match lines.next().transpose()? {
+                        Some(text) => text,
+                        None => return Ok(None),
+                    },
+                };
+                *line += 1;
+                if text.trim().is_empty() {
+                    continue;
+                }
+                let values = json_values(&text, columns);
+                return Ok(Some(Record {
+                    line: *line,
+                    raw: Raw::Json(text),
+                    values,
+                }));
+            },
+        }
+    }
+}
+
+fn csv_values(record: &csv::ByteRecord, width: usize) -> anyhow::Result<Vec<Option<String>>> {
+    if record.len() != width {
+        anyhow::bail!("expected {width} fields, found {}", record.len());
+    }
+    record
+        .iter()
+        .map(|cell| {
+            let text = std::str::from_utf8(cell).context("invalid UTF-8")?;
+            Ok((!text.is_empty()).then(|| text.to_string()))
+        })
+        .collect()
+}
+
+/// JSON strings are used as is, other values are parsed from their JSON text
+/// (same as `--args-file` of `query` command)
+fn json_values(text: &str, columns: &[String]) -> anyhow::Result<Vec<Option<String>>> {
+    let mut object: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(text).context("not a JSON object")?;
+    let values = columns
+        .iter()
+        .map(|column| match object.remove(column) {
+            None | Some(serde_json::Value::Null) => None,
+            Some(serde_json::Value::String(s)) => Some(s),
+            Some(value) => Some(value.to_string()),
+        })
+        .collect();
+    if let Some(key) = object.keys().next() {
+        anyhow::bail!("unexpected key {key:?}, all objects must have the same keys");
+    }
+    Ok(values)
+}
+
+impl Rejects {
+    fn new(
+        path: Option<&Path>,
+        format: ImportFormat,
+        columns: &[String],
+    ) -> anyhow::Result<Rejects> {
+        let writer = match path {
+            None => None,
+            Some(path) => {
+                let file =
+                    fs::File::create(path).with_context(|| format!("cannot create {path:?}"))?;
+                Some(match format {
+                    ImportFormat::Csv => {
+                        // malformed rows are written as they were read
+                        let mut writer = csv::WriterBuilder::new().flexible(true).from_writer(file);
+                        writer.write_record(columns)?;
+                        RejectWriter::Csv(writer)
+                    }
+                    ImportFormat::Jsonl => RejectWriter::Json(io::BufWriter::new(file)),
+                })
+            }
+        };
+        Ok(Rejects { writer, count: 0 })
+    }
+
+    fn add(&mut self, record: &Record, error: &anyhow::Error) -> anyhow::Result<()> {
+        print::error!("line {}: {error:#}", record.line);
+        self.count += 1;
+        match (&mut self.writer, &record.raw) {
+            (Some(RejectWriter::Csv(writer)), Raw::Csv(raw)) => writer.write_byte_record(raw)?,
+            (Some(RejectWriter::Json(writer)), Raw::Json(raw)) => writeln!(writer, "{raw}")?,
+            (None, _) => {}
+            _ => unreachable!("reject file has the same format as input"),
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        match &mut self.writer {
+            Some(RejectWriter::Csv(writer)) => writer.flush()?,
+            Some(RejectWriter::Json(writer)) => writer.flush()?,
+            None => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,133 @@
+use prettytable::{Cell, Row, Table};
+
+use gel_derive::Queryable;
+use std::io::IsTerminal;
+
+use crate::commands::Options;
+use crate::commands::filter;
+use crate::commands::helpers::quote_namespaced;
+use crate::connect::Connection;
+use crate::table;
+
+#[derive(Queryable)]
+struct Global {
+    name: String,
+    type_name: String,
+    default: String,
+    computed: bool,
+}
+
+pub async fn list_globals(
+    cli: &mut Connection,
+    options: &Options,
+    pattern: &Option<String>,
+    system: bool,
+    case_sensitive: bool,
+    verbose: bool,
+) -> Result<(), anyhow::Error> {
+    let mut filters = Vec::with_capacity(2);
+    if !system {
+        filters.push(
+            r#"NOT re_test("^(?:std|schema|math|sys|cfg|cal|stdgraphql)::",
+               .name)"#,
+        );
+    }
+    if pattern.is_some() {
+        filters.push("re_test(<str>$0, .name)");
+    }
+    let filter = if filters.is_empty() {
+        String::from("")
+    } else {
+        format!("FILTER {}", filters.join(" AND "))
+    };
+    let query = &format!(
+        r###"
+        WITH MODULE schema
+        SELECT Global {{
+            name,
+            type_name := (
+                'multi ' IF .cardinality = Cardinality.Many ELSE ''
+            ) ++ .target.name,
+            default := .expr ?? .default ?? '',
+            computed := EXISTS .expr,
+        }}
+        {filter}
+        ORDER BY .name;
+    "###
+    );
+    let items = filter::query::<Global>(cli, query, pattern, case_sensitive).await?;
+
+    // current values, as seen by the session (i.e. including `SET GLOBAL`)
+    let mut values = Vec::with_capacity(items.len());
+    for item in &items {
+        let value = cli
+            .query::<String, _>(
+                &format!(
+                    "SELECT to_str(<json>(GLOBAL {}))",
+                    quote_namespaced(&item.name)
+                ),
+                &(),
+            )
+            .await?;
+        values.push(if value.is_empty() {
+            String::from("{}")
+        } else {
+            value.join(", ")
+        });
+    }
+
+    if !options.command_line || std::io::stdout().is_terminal() {
+        let mut table = Table::new();
+        table.set_format(*table::FORMAT);
+        let mut titles = vec!["Name", "Type", "Value"];
+        if verbose {
+            titles.push("Default / Expression");
+        }
+        table.set_titles(Row::new(
+            titles.iter().map(|x| table::header_cell(x)).collect(),
+        ));
+        for (item, value) in items.iter().zip(&values) {
+            let mut row = vec![
+                Cell::new(&item.name),
+                Cell::new(&item.type_name),
+                Cell::new(value),
+            ];
+            if verbose {
+                if item.computed {
+                    row.push(Cell::new(&format!(":= {}", item.default)));
+                } else {
+                    row.push(Cell::new(&item.default));
+                }
+            }
+            table.add_row(Row::new(row));
+        }
+        if table.is_empty() {
+            if let Some(pattern) = pattern {
+                eprintln!("No globals found matching {pattern:?}");
+            } else if !system {
+                eprintln!(
+                    "No user-defined globals found. {}",
+                    if options.command_line {
+                        "Try --system"
+                    } else {
+                        r"Try \lg -s"
+                    }
+                );
+            }
+        } else {
+            table.printstd();
+        }
+    } else if verbose {
+        for (item, value) in items.iter().zip(&values) {
+            println!(
+                "{}\t{}\t{}\t{}",
+                item.name, item.type_name, value, item.default
+            );
+        }
+    } else {
+        for (item, value) in items.iter().zip(&values) {
+            println!("{}\t{}\t{}", item.name, item.type_name, value);
+        }
+    }
+    Ok(())
+}
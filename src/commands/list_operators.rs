@@ -0,0 +1,125 @@
+use prettytable::{Cell, Row, Table};
+
+use gel_derive::Queryable;
+use std::io::IsTerminal;
+use terminal_size::{Width, terminal_size};
+
+use crate::commands::Options;
+use crate::commands::filter;
+use crate::commands::list_functions::SIGNATURE_FIELDS;
+use crate::connect::Connection;
+use crate::table;
+
+#[derive(Queryable)]
+struct Operator {
+    name: String,
+    kind: String,
+    signature: String,
+    returns: String,
+    volatility_str: String,
+    description: String,
+}
+
+pub async fn list_operators(
+    cli: &mut Connection,
+    options: &Options,
+    pattern: &Option<String>,
+    system: bool,
+    case_sensitive: bool,
+    verbose: bool,
+) -> Result<(), anyhow::Error> {
+    let mut filters = Vec::with_capacity(2);
+    if !system {
+        filters.push(
+            r#"NOT re_test("^(?:std|schema|math|sys|cfg|cal|stdgraphql)::",
+               .name)"#,
+        );
+    }
+    if pattern.is_some() {
+        filters.push("re_test(<str>$0, .name)");
+    }
+    let filter = if filters.is_empty() {
+        String::from("")
+    } else {
+        format!("FILTER {}", filters.join(" AND "))
+    };
+    let query = &format!(
+        r###"
+        WITH MODULE schema
+        SELECT Operator {{
+            name,
+            kind := str_lower(<str>.operator_kind),
+            {SIGNATURE_FIELDS}
+        }}
+        {filter}
+        ORDER BY .name;
+    "###
+    );
+
+    let items = filter::query::<Operator>(cli, query, pattern, case_sensitive).await?;
+    if !options.command_line || std::io::stdout().is_terminal() {
+        let term_width: usize = terminal_size().map(|(Width(w), _h)| w.into()).unwrap_or(80);
+        let signature_width = (term_width.saturating_sub(10) / 3).max(10);
+        let mut table = Table::new();
+        table.set_format(*table::FORMAT);
+        let mut titles = vec!["Name", "Kind", "Parameters", "Returns", "Volatility"];
+        if verbose {
+            titles.push("Description");
+        }
+        table.set_titles(Row::new(
+            titles.iter().map(|x| table::header_cell(x)).collect(),
+        ));
+        for item in items {
+            let mut row = vec![
+                Cell::new(&item.name),
+                Cell::new(&item.kind),
+                Cell::new(&textwrap::fill(&item.signature, signature_width)),
+                Cell::new(&item.returns),
+                Cell::new(&item.volatility_str),
+            ];
+            if verbose {
+                row.push(Cell::new(&textwrap::fill(
+                    &item.description,
+                    signature_width,
+                )));
+            }
+            table.add_row(Row::new(row));
+        }
+        if table.is_empty() {
+            if let Some(pattern) = pattern {
+                eprintln!("No operators found matching {pattern:?}");
+            } else if !system {
+                eprintln!(
+                    "No user-defined operators found. {}",
+                    if options.command_line {
+                        "Try --system"
+                    } else {
+                        r"Try \lo -s"
+                    }
+                );
+            }
+        } else {
+            table.printstd();
+        }
+    } else if verbose {
+        for item in items {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                item.name,
+                item.kind,
+                item.signature,
+                item.returns,
+                item.volatility_str,
+                item.description
+            );
+        }
+    } else {
+        for item in items {
+            println!(
+                "{}\t{}\t{}\t{}\t{}",
+                item.name, item.kind, item.signature, item.returns, item.volatility_str
+            );
+        }
+    }
+    Ok(())
+}
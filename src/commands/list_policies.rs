@@ -0,0 +1,120 @@
+use prettytable::{Cell, Row, Table};
+
+use gel_derive::Queryable;
+use std::io::IsTerminal;
+
+use crate::commands::Options;
+use crate::commands::filter;
+use crate::connect::Connection;
+use crate::table;
+
+#[derive(Queryable)]
+struct Policy {
+    subject_name: String,
+    name: String,
+    action_str: String,
+    kinds: String,
+    condition: String,
+    expr: String,
+    errmessage: String,
+}
+
+pub async fn list_policies(
+    cli: &mut Connection,
+    options: &Options,
+    pattern: &Option<String>,
+    system: bool,
+    case_sensitive: bool,
+    verbose: bool,
+) -> Result<(), anyhow::Error> {
+    let mut filters = Vec::with_capacity(2);
+    if !system {
+        filters.push(
+            r#"NOT re_test("^(?:std|schema|math|sys|cfg|cal|stdgraphql)::",
+               .subject_name)"#,
+        );
+    }
+    if pattern.is_some() {
+        filters.push("re_test(<str>$0, .subject_name)");
+    }
+    let filter = if filters.is_empty() {
+        String::from("")
+    } else {
+        format!("FILTER {}", filters.join(" AND "))
+    };
+    let query = &format!(
+        r###"
+        WITH MODULE schema
+        SELECT AccessPolicy {{
+            subject_name := .<access_policies[IS ObjectType].name,
+            name,
+            action_str := str_lower(<str>.action),
+            kinds := array_join(
+                array_agg(str_lower(<str>.access_kinds)), ', '),
+            condition := .condition ?? '',
+            expr := .expr ?? '',
+            errmessage := .errmessage ?? '',
+        }}
+        {filter}
+        ORDER BY .subject_name THEN .name;
+    "###
+    );
+    let items = filter::query::<Policy>(cli, query, pattern, case_sensitive).await?;
+    if !options.command_line || std::io::stdout().is_terminal() {
+        let mut table = Table::new();
+        table.set_format(*table::FORMAT);
+        let mut titles = vec!["Type", "Policy", "Action", "Access Kinds", "Using"];
+        if verbose {
+            titles.extend(["When", "Error Message"]);
+        }
+        table.set_titles(Row::new(
+            titles.iter().map(|x| table::header_cell(x)).collect(),
+        ));
+        for item in items {
+            let mut row = vec![
+                Cell::new(&item.subject_name),
+                Cell::new(&item.name),
+                Cell::new(&item.action_str),
+                Cell::new(&item.kinds),
+                Cell::new(&item.expr),
+            ];
+            if verbose {
+                row.push(Cell::new(&item.condition));
+                row.push(Cell::new(&item.errmessage));
+            }
+            table.add_row(Row::new(row));
+        }
+        if table.is_empty() {
+            if let Some(pattern) = pattern {
+                eprintln!("No access policies found on types matching {pattern:?}");
+            } else if !system {
+                eprintln!("No access policies found on user-defined types.");
+            } else {
+                eprintln!("No access policies found.");
+            }
+        } else {
+            table.printstd();
+        }
+    } else if verbose {
+        for item in items {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                item.subject_name,
+                item.name,
+                item.action_str,
+                item.kinds,
+                item.expr,
+                item.condition,
+                item.errmessage
+            );
+        }
+    } else {
+        for item in items {
+            println!(
+                "{}\t{}\t{}\t{}\t{}",
+                item.subject_name, item.name, item.action_str, item.kinds, item.expr
+            );
+        }
+    }
+    Ok(())
+}
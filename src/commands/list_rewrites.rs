@@ -0,0 +1,92 @@
+use prettytable::{Cell, Row, Table};
+
+use gel_derive::Queryable;
+use std::io::IsTerminal;
+
+use crate::commands::Options;
+use crate::commands::filter;
+use crate::connect::Connection;
+use crate::table;
+
+#[derive(Queryable)]
+struct Rewrite {
+    subject_name: String,
+    pointer_name: String,
+    kind_str: String,
+    expr: String,
+}
+
+pub async fn list_rewrites(
+    cli: &mut Connection,
+    options: &Options,
+    pattern: &Option<String>,
+    system: bool,
+    case_sensitive: bool,
+) -> Result<(), anyhow::Error> {
+    let mut filters = Vec::with_capacity(2);
+    if !system {
+        filters.push(
+            r#"NOT re_test("^(?:std|schema|math|sys|cfg|cal|stdgraphql)::",
+               .subject_name)"#,
+        );
+    }
+    if pattern.is_some() {
+        filters.push("re_test(<str>$0, .subject_name)");
+    }
+    let filter = if filters.is_empty() {
+        String::from("")
+    } else {
+        format!("FILTER {}", filters.join(" AND "))
+    };
+    let query = &format!(
+        r###"
+        WITH MODULE schema
+        SELECT Rewrite {{
+            subject_name := .subject.<pointers[IS ObjectType].name,
+            pointer_name := .subject.name,
+            kind_str := str_lower(<str>.kind),
+            expr,
+        }}
+        {filter}
+        ORDER BY .subject_name THEN .pointer_name THEN .kind;
+    "###
+    );
+    let items = filter::query::<Rewrite>(cli, query, pattern, case_sensitive).await?;
+    if !options.command_line || std::io::stdout().is_terminal() {
+        let mut table = Table::new();
+        table.set_format(*table::FORMAT);
+        table.set_titles(Row::new(
+            ["Type", "Pointer", "Kind", "Expression"]
+                .iter()
+                .map(|x| table::header_cell(x))
+                .collect(),
+        ));
+        for item in items {
+            table.add_row(Row::new(vec![
+                Cell::new(&item.subject_name),
+                Cell::new(&item.pointer_name),
+                Cell::new(&item.kind_str),
+                Cell::new(&item.expr),
+            ]));
+        }
+        if table.is_empty() {
+            if let Some(pattern) = pattern {
+                eprintln!("No mutation rewrites found on types matching {pattern:?}");
+            } else if !system {
+                eprintln!("No mutation rewrites found on user-defined types.");
+            } else {
+                eprintln!("No mutation rewrites found.");
+            }
+        } else {
+            table.printstd();
+        }
+    } else {
+        for item in items {
+            println!(
+                "{}\t{}\t{}\t{}",
+                item.subject_name, item.pointer_name, item.kind_str, item.expr
+            );
+        }
+    }
+    Ok(())
+}
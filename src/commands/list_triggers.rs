@@ -0,0 +1,127 @@
+use prettytable::{Cell, Row, Table};
+
+use gel_derive::Queryable;
+use std::io::IsTerminal;
+
+use crate::commands::Options;
+use crate::commands::filter;
+use crate::connect::Connection;
+use crate::table;
+
+#[derive(Queryable)]
+struct Trigger {
+    subject_name: String,
+    name: String,
+    timing_str: String,
+    kinds_str: String,
+    scope_str: String,
+    condition: String,
+    expr: String,
+}
+
+pub async fn list_triggers(
+    cli: &mut Connection,
+    options: &Options,
+    pattern: &Option<String>,
+    system: bool,
+    case_sensitive: bool,
+    verbose: bool,
+) -> Result<(), anyhow::Error> {
+    let mut filters = Vec::with_capacity(2);
+    if !system {
+        filters.push(
+            r#"NOT re_test("^(?:std|schema|math|sys|cfg|cal|stdgraphql)::",
+               .subject_name)"#,
+        );
+    }
+    if pattern.is_some() {
+        filters.push("re_test(<str>$0, .subject_name)");
+    }
+    let filter = if filters.is_empty() {
+        String::from("")
+    } else {
+        format!("FILTER {}", filters.join(" AND "))
+    };
+    let query = &format!(
+        r###"
+        WITH MODULE schema
+        SELECT Trigger {{
+            subject_name := .<triggers[IS ObjectType].name,
+            name,
+            timing_str := (
+                'after commit of' IF .timing = TriggerTiming.AfterCommitOf
+                ELSE 'after'
+            ),
+            kinds_str := array_join(array_agg(str_lower(<str>.kinds)), ', '),
+            scope_str := str_lower(<str>.scope),
+            condition := .condition ?? '',
+            expr,
+        }}
+        {filter}
+        ORDER BY .subject_name THEN .name;
+    "###
+    );
+    let items = filter::query::<Trigger>(cli, query, pattern, case_sensitive).await?;
+    if !options.command_line || std::io::stdout().is_terminal() {
+        let mut table = Table::new();
+        table.set_format(*table::FORMAT);
+        let mut titles = vec!["Type", "Trigger", "Timing", "Kinds", "Scope", "When"];
+        if verbose {
+            titles.push("Expression");
+        }
+        table.set_titles(Row::new(
+            titles.iter().map(|x| table::header_cell(x)).collect(),
+        ));
+        for item in items {
+            let mut row = vec![
+                Cell::new(&item.subject_name),
+                Cell::new(&item.name),
+                Cell::new(&item.timing_str),
+                Cell::new(&item.kinds_str),
+                Cell::new(&item.scope_str),
+                Cell::new(&item.condition),
+            ];
+            if verbose {
+                row.push(Cell::new(&item.expr));
+            }
+            table.add_row(Row::new(row));
+        }
+        if table.is_empty() {
+            if let Some(pattern) = pattern {
+                eprintln!("No triggers found on types matching {pattern:?}");
+            } else if !system {
+                eprintln!("No triggers found on user-defined types.");
+            } else {
+                eprintln!("No triggers found.");
+            }
+        } else {
+            table.printstd();
+        }
+    } else if verbose {
+        for item in items {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                item.subject_name,
+                item.name,
+                item.timing_str,
+                item.kinds_str,
+                item.scope_str,
+                item.condition,
+                item.expr
+            );
+        }
+    } else {
+        for item in items {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                item.subject_name,
+                item.name,
+                item.timing_str,
+                item.kinds_str,
+                item.scope_str,
+                item.condition
+            );
+        }
+    }
+    Ok(())
+}
@@ -10,37 +10,53 @@ mod exit;
 mod filter;
 pub mod generate;
 mod helpers;
+mod import;
 mod info;
 mod list;
 mod list_aliases;
 mod list_branches;
 mod list_casts;
 mod list_databases;
+mod list_functions;
+mod list_globals;
 mod list_indexes;
 mod list_modules;
 mod list_object_types;
+mod list_operators;
+mod list_policies;
+mod list_rewrites;
 mod list_roles;
 mod list_scalar_types;
+mod list_triggers;
 pub mod options;
 pub mod parser;
 mod psql;
 mod restore;
+mod schema_graph;
+mod schema_json;
 mod ui;
 
 pub use self::describe::describe;
 pub use self::describe_schema::describe_schema;
 pub use self::dump::{dump, dump_all};
 pub use self::exit::ExitCode;
+pub use self::import::import;
 pub use self::info::info;
 pub use self::list_aliases::list_aliases;
 pub use self::list_branches::list_branches;
 pub use self::list_casts::list_casts;
 pub use self::list_databases::list_databases;
+pub use self::list_functions::list_functions;
+pub use self::list_globals::list_globals;
 pub use self::list_indexes::list_indexes;
 pub use self::list_modules::list_modules;
 pub use self::list_object_types::list_object_types;
+pub use self::list_operators::list_operators;
+pub use self::list_policies::list_policies;
+pub use self::list_rewrites::list_rewrites;
 pub use self::list_roles::list_roles;
 pub use self::list_scalar_types::list_scalar_types;
+pub use self::list_triggers::list_triggers;
 pub use self::options::Options;
 pub use self::psql::psql;
 pub use self::restore::{restore, restore_all};
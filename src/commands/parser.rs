@@ -17,6 +17,8 @@ pub enum Common {
     Dump(Dump),
     /// Restore database from backup file
     Restore(Restore),
+    /// Insert objects from a CSV or JSON Lines file
+    Import(Import),
     /// Modify database configuration
     Configure(crate::commands::configure::Command),
 
@@ -116,16 +118,28 @@ pub enum ListCmd {
     Databases,
     /// On EdgeDB/Gel >= 5.x: Display list of branches for an instance
     Branches,
+    /// Display list of functions with their signatures
+    Functions(ListFunctions),
+    /// Display list of global variables and their current values
+    Globals(ListGlobals),
     /// Display list of indexes defined in the schema
     Indexes(ListIndexes),
     /// Display list of modules defined in the schema
     Modules(ListModules),
+    /// Display list of operators with their signatures
+    Operators(ListFunctions),
+    /// Display list of access policies defined on object types
+    Policies(ListTypeItems),
+    /// Display list of mutation rewrites defined on object types
+    Rewrites(ListRewrites),
     /// Display list of roles for an instance
     Roles(ListRoles),
     /// Display list of scalar types defined in the schema
     Scalars(ListTypes),
     /// Display list of object types defined in the schema
     Types(ListTypes),
+    /// Display list of triggers defined on object types
+    Triggers(ListTypeItems),
 }
 
 #[derive(clap::Args, Clone, Debug)]
@@ -170,6 +184,9 @@ pub enum BackslashCmd {
     History,
     Connect(Connect),
     Edit(Edit),
+    Watch(Watch),
+    Output(Output),
+    Include(Include),
     Set(SetCommand),
     Exit,
 }
@@ -219,6 +236,8 @@ pub enum Setting {
     HistorySize(SettingUsize),
     /// Print statistics on each query
     PrintStats(PrintStats),
+    /// How nested data is written in CSV output. One of: json, join
+    CsvFlatten(CsvFlatten),
     /// Set idle transaction timeout in Duration format.
     /// Default is 5 minutes; specify 0 to disable.
     IdleTransactionTimeout(IdleTransactionTimeout),
@@ -271,6 +290,37 @@ pub struct Edit {
     pub entry: Option<isize>,
 }
 
+/// Re-run a query periodically until interrupted with Ctrl+C
+#[derive(clap::Args, Clone, Debug)]
+pub struct Watch {
+    /// Interval between runs: seconds or a duration like `500ms` (default 2),
+    /// followed by the query to run instead of the last executed one. If the
+    /// first argument is not an interval, all arguments are the query
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub args: Vec<String>,
+}
+
+/// Write query results to a file instead of the terminal
+#[derive(clap::Args, Clone, Debug)]
+pub struct Output {
+    /// Print results to the terminal too
+    #[arg(short = 't', long)]
+    pub tee: bool,
+    /// Append to the file instead of truncating it
+    #[arg(short = 'a', long)]
+    pub append: bool,
+    /// File to write results to. Without it, output goes back to the terminal
+    #[arg(value_name = "file")]
+    pub file: Option<PathBuf>,
+}
+
+/// Execute queries from a file in the current session
+#[derive(clap::Args, Clone, Debug)]
+pub struct Include {
+    #[arg(value_name = "file")]
+    pub file: PathBuf,
+}
+
 #[derive(clap::Args, Clone, Debug, Default)]
 pub struct OutputFormat {
     #[arg(value_name = "mode")]
@@ -282,6 +332,12 @@ pub struct PrintStats {
     pub value: Option<repl::PrintStats>,
 }
 
+#[derive(clap::Args, Clone, Debug, Default)]
+pub struct CsvFlatten {
+    #[arg(value_name = "mode")]
+    pub value: Option<repl::CsvFlatten>,
+}
+
 #[derive(clap::Args, Clone, Debug)]
 pub struct Connect {
     pub database_name: String,
@@ -306,6 +362,14 @@ pub struct WipeDatabase {
     #[arg(long)]
     pub non_interactive: bool,
 
+    /// Proceed even if the branch is protected in the project manifest
+    #[arg(long)]
+    pub force_protected: bool,
+
+    /// Do not save a snapshot of the database before wiping it
+    #[arg(long)]
+    pub no_snapshot: bool,
+
     #[command(flatten)]
     pub instance_opts: InstanceOptions,
 }
@@ -328,6 +392,55 @@ pub struct ListCasts {
     pub case_sensitive: bool,
 }
 
+#[derive(clap::Args, Clone, Debug)]
+pub struct ListFunctions {
+    pub pattern: Option<String>,
+    #[arg(long, short = 'c')]
+    pub case_sensitive: bool,
+    #[arg(long, short = 's')]
+    pub system: bool,
+    /// Show descriptions
+    #[arg(long, short = 'v')]
+    pub verbose: bool,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct ListGlobals {
+    pub pattern: Option<String>,
+    #[arg(long, short = 'c')]
+    pub case_sensitive: bool,
+    #[arg(long, short = 's')]
+    pub system: bool,
+    /// Show default values and expressions of computed globals
+    #[arg(long, short = 'v')]
+    pub verbose: bool,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct ListTypeItems {
+    /// Show only items of object types matching this pattern
+    #[arg(value_name = "TYPE")]
+    pub pattern: Option<String>,
+    #[arg(long, short = 'c')]
+    pub case_sensitive: bool,
+    #[arg(long, short = 's')]
+    pub system: bool,
+    /// Show all expressions
+    #[arg(long, short = 'v')]
+    pub verbose: bool,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct ListRewrites {
+    /// Show only rewrites of object types matching this pattern
+    #[arg(value_name = "TYPE")]
+    pub pattern: Option<String>,
+    #[arg(long, short = 'c')]
+    pub case_sensitive: bool,
+    #[arg(long, short = 's')]
+    pub system: bool,
+}
+
 #[derive(clap::Args, Clone, Debug)]
 pub struct ListIndexes {
     pub pattern: Option<String>,
@@ -369,8 +482,35 @@ pub struct DescribeObject {
     pub verbose: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SchemaFormat {
+    Sdl,
+    Json,
+    Dot,
+    Mermaid,
+}
+
 #[derive(clap::Args, Clone, Debug)]
-pub struct DescribeSchema {}
+pub struct DescribeSchema {
+    /// Output format: `sdl` (default), `json` document for tools, or a
+    /// diagram of object types, their links and inheritance as Graphviz
+    /// `dot` or `mermaid` class diagram
+    #[arg(long, value_enum, default_value = "sdl")]
+    pub format: SchemaFormat,
+
+    /// Same as `--format=mermaid`
+    #[arg(long, conflicts_with = "format")]
+    pub graph: bool,
+
+    /// Only describe this module (can be repeated). Not supported for `sdl`
+    #[arg(long, value_name = "NAME")]
+    pub module: Vec<String>,
+
+    /// Write a JSON file per module into this directory instead of
+    /// printing the schema. Requires `--format=json`
+    #[arg(long, value_hint=clap::ValueHint::DirPath)]
+    pub output_dir: Option<PathBuf>,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
 pub enum DumpFormat {
@@ -432,6 +572,73 @@ pub struct Restore {
     pub verbose: bool,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    Csv,
+    Jsonl,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct Import {
+    #[command(flatten)]
+    pub conn: ConnectionOptions,
+
+    /// CSV file with a header row or JSON Lines file with one object per
+    /// line. Columns (keys) are matched to properties by name
+    #[arg(value_hint=clap::ValueHint::FilePath)]
+    pub file: PathBuf,
+
+    /// Object type to insert into, e.g. `default::User`
+    #[arg(long = "type", value_name = "TYPE")]
+    pub type_name: String,
+
+    /// File format. Detected from the file extension by default
+    /// (`.csv`, `.jsonl` or `.ndjson`)
+    #[arg(long, value_enum)]
+    pub format: Option<ImportFormat>,
+
+    /// Number of rows inserted in a single transaction
+    #[arg(long, default_value = "1000", value_parser = clap::value_parser!(u64).range(1..))]
+    pub batch_size: u64,
+
+    /// Write rows that failed to import into this file (in the same format
+    /// as the input), so they can be fixed and imported again
+    #[arg(long, value_hint=clap::ValueHint::FilePath)]
+    pub reject_file: Option<PathBuf>,
+}
+
+impl Watch {
+    /// Returns the interval and the query, if they are specified
+    pub fn interval_and_query(
+        &self,
+    ) -> anyhow::Result<(Option<std::time::Duration>, Option<String>)> {
+        let Some((first, rest)) = self.args.split_first() else {
+            return Ok((None, None));
+        };
+        // anything starting like a number is an interval, even if malformed
+        let is_interval = first.starts_with(|c: char| c.is_ascii_digit() || c == '.' || c == '-');
+        if !is_interval {
+            return Ok((None, Some(self.args.join(" "))));
+        }
+        let interval = parse_watch_interval(first)
+            .map_err(|e| anyhow::anyhow!("invalid interval {first:?}: {e}"))?;
+        let query = (!rest.is_empty()).then(|| rest.join(" "));
+        Ok((Some(interval), query))
+    }
+}
+
+fn parse_watch_interval(value: &str) -> Result<std::time::Duration, String> {
+    let interval = match value.parse::<f64>() {
+        Ok(secs) if secs.is_finite() && secs >= 0.0 => std::time::Duration::from_secs_f64(secs),
+        Ok(_) => return Err("interval must be a non-negative number of seconds".into()),
+        Err(_) => humantime::parse_duration(value).map_err(|e| e.to_string())?,
+    };
+    if interval.is_zero() {
+        return Err("interval must be greater than zero".into());
+    }
+    Ok(interval)
+}
+
 impl SettingBool {
     pub fn unwrap_value(&self) -> bool {
         match self.value.as_deref() {
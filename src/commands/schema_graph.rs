@@ -0,0 +1,422 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+use gel_derive::Queryable;
+use serde::Deserialize;
+
+use crate::commands::schema_json::module_of;
+use crate::connect::Connection;
+
+const INTROSPECTION_QUERY: &str = r###"
+    WITH MODULE schema
+    SELECT <json>{
+        object_types := array_agg((
+            SELECT ObjectType {
+                name,
+                is_abstract,
+                base_names := array_agg(.bases.name),
+                ancestor_names := array_agg(.ancestors.name),
+                properties: {
+                    name,
+                    target_name := .target.name,
+                    required,
+                    multi := .cardinality = Cardinality.Many,
+                    computed := EXISTS .expr,
+                } FILTER .name != 'id' ORDER BY .name,
+                links: {
+                    name,
+                    target_name := .target.name,
+                    required,
+                    multi := .cardinality = Cardinality.Many,
+                    computed := EXISTS .expr,
+                    expr,
+                } FILTER .name != '__type__' ORDER BY .name,
+            }
+            FILTER NOT .is_compound_type AND NOT .is_from_alias
+                AND NOT re_test(
+                    "^(?:std|schema|math|sys|cfg|cal|stdgraphql)::", .name)
+            ORDER BY .name
+        )),
+    }
+"###;
+
+#[derive(Deserialize, Queryable, Debug)]
+#[gel(json)]
+struct RawSchema {
+    object_types: Vec<ObjectType>,
+}
+
+/// Object types of the schema with the pointers they define themselves
+/// (inherited ones are shown on the base type only)
+#[derive(Debug)]
+pub struct SchemaGraph {
+    types: Vec<ObjectType>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ObjectType {
+    name: String,
+    is_abstract: bool,
+    #[serde(rename = "base_names")]
+    bases: Vec<String>,
+    #[serde(rename = "ancestor_names")]
+    ancestors: Vec<String>,
+    properties: Vec<Pointer>,
+    links: Vec<Pointer>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Pointer {
+    name: String,
+    target_name: String,
+    required: bool,
+    multi: bool,
+    computed: bool,
+    #[serde(default)]
+    expr: Option<String>,
+}
+
+pub async fn fetch(cli: &mut Connection) -> anyhow::Result<SchemaGraph> {
+    let raw = cli
+        .query_required_single::<RawSchema, _>(INTROSPECTION_QUERY, &())
+        .await?;
+    Ok(SchemaGraph::new(raw.object_types))
+}
+
+impl SchemaGraph {
+    fn new(mut types: Vec<ObjectType>) -> SchemaGraph {
+        let owned: BTreeMap<String, BTreeSet<String>> = types
+            .iter()
+            .map(|t| {
+                let names = t.properties.iter().chain(&t.links);
+                (t.name.clone(), names.map(|p| p.name.clone()).collect())
+            })
+            .collect();
+        for typ in &mut types {
+            let inherited = |p: &Pointer| {
+                typ.ancestors
+                    .iter()
+                    .filter_map(|a| owned.get(a))
+                    .any(|names| names.contains(&p.name))
+            };
+            let properties = std::mem::take(&mut typ.properties);
+            typ.properties = properties.into_iter().filter(|p| !inherited(p)).collect();
+            let links = std::mem::take(&mut typ.links);
+            typ.links = links.into_iter().filter(|p| !inherited(p)).collect();
+        }
+        SchemaGraph { types }
+    }
+
+    /// Leaves only types defined in the given modules
+    pub fn retain_modules(&mut self, modules: &[String]) {
+        self.types
+            .retain(|t| modules.iter().any(|m| m == module_of(&t.name)));
+    }
+
+    fn is_rendered(&self, name: &str) -> bool {
+        self.types.iter().any(|t| t.name == name)
+    }
+
+    /// Link targets that are not rendered themselves (e.g. types filtered
+    /// out by module), they are drawn as plain nodes
+    fn external_targets(&self) -> BTreeSet<&str> {
+        self.types
+            .iter()
+            .flat_map(|t| &t.links)
+            .map(|l| l.target_name.as_str())
+            .filter(|name| !self.is_rendered(name))
+            .collect()
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph schema {\n");
+        out.push_str("    rankdir=LR;\n");
+        out.push_str("    node [shape=record];\n");
+        for typ in &self.types {
+            let mut label = String::new();
+            if typ.is_abstract {
+                label.push_str("«abstract»\\n");
+            }
+            label.push_str(&dot_escape(&typ.name));
+            label.push('|');
+            for prop in &typ.properties {
+                label.push_str(&dot_escape(&prop.describe()));
+                label.push_str("\\l");
+            }
+            writeln!(out, "    {} [label=\"{{{label}}}\"];", dot_id(&typ.name)).unwrap();
+        }
+        for name in self.external_targets() {
+            writeln!(
+                out,
+                "    {} [label=\"{}\"];",
+                dot_id(name),
+                dot_escape(name)
+            )
+            .unwrap();
+        }
+        for typ in &self.types {
+            for base in typ.bases.iter().filter(|b| self.is_rendered(b)) {
+                writeln!(
+                    out,
+                    "    {} -> {} [arrowhead=empty];",
+                    dot_id(&typ.name),
+                    dot_id(base),
+                )
+                .unwrap();
+            }
+            for link in &typ.links {
+                write!(
+                    out,
+                    "    {} -> {} [label={}",
+                    dot_id(&typ.name),
+                    dot_id(&link.target_name),
+                    dot_id(&link.label()),
+                )
+                .unwrap();
+                if link.computed {
+                    out.push_str(", style=dashed");
+                }
+                out.push_str("];\n");
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("classDiagram\n");
+        for typ in &self.types {
+            let id = mermaid_id(&typ.name);
+            writeln!(out, "    class {id}[\"{}\"]", typ.name).unwrap();
+            if typ.is_abstract {
+                writeln!(out, "    <<abstract>> {id}").unwrap();
+            }
+            for prop in &typ.properties {
+                writeln!(out, "    {id} : {}", prop.describe()).unwrap();
+            }
+        }
+        for name in self.external_targets() {
+            writeln!(out, "    class {}[\"{name}\"]", mermaid_id(name)).unwrap();
+        }
+        for typ in &self.types {
+            let id = mermaid_id(&typ.name);
+            for base in typ.bases.iter().filter(|b| self.is_rendered(b)) {
+                writeln!(out, "    {} <|-- {id}", mermaid_id(base)).unwrap();
+            }
+            for link in &typ.links {
+                write!(
+                    out,
+                    "    {id} {} \"{}\" {} : {}",
+                    if link.computed { "..>" } else { "-->" },
+                    link.multiplicity(),
+                    mermaid_id(&link.target_name),
+                    link.name,
+                )
+                .unwrap();
+                if let Some(forward) = link.backlink_of() {
+                    write!(out, " (backlink of {forward})").unwrap();
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+impl Pointer {
+    fn describe(&self) -> String {
+        let mut text = String::new();
+        if self.required {
+            text.push_str("required ");
+        }
+        if self.multi {
+            text.push_str("multi ");
+        }
+        text.push_str(&self.name);
+        text.push_str(if self.computed { " := " } else { ": " });
+        text.push_str(
+            self.target_name
+                .strip_prefix("std::")
+                .unwrap_or(&self.target_name),
+        );
+        text
+    }
+
+    /// Name of the link this one is a backlink of, i.e. `author` for
+    /// `posts := .<author[IS Post]`
+    fn backlink_of(&self) -> Option<&str> {
+        let rest = self.expr.as_deref()?.trim_start().strip_prefix(".<")?;
+        let rest = rest.trim_start().trim_start_matches('`');
+        let end = rest
+            .find(|c: char| !c.is_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        (end > 0).then(|| &rest[..end])
+    }
+
+    fn label(&self) -> String {
+        match self.backlink_of() {
+            Some(forward) => format!(
+                "{} {} (backlink of {forward})",
+                self.name,
+                self.multiplicity()
+            ),
+            None => format!("{} {}", self.name, self.multiplicity()),
+        }
+    }
+
+    fn multiplicity(&self) -> &'static str {
+        match (self.required, self.multi) {
+            (true, false) => "1",
+            (false, false) => "0..1",
+            (true, true) => "1..*",
+            (false, true) => "0..*",
+        }
+    }
+}
+
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Escapes text for use in a `record` label
+fn dot_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn mermaid_id(name: &str) -> String {
+    name.replace("::", "__")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ObjectType, Pointer, SchemaGraph};
+
+    fn pointer(name: &str, target: &str, required: bool, multi: bool) -> Pointer {
+        Pointer {
+            name: name.into(),
+            target_name: target.into(),
+            required,
+            multi,
+            computed: false,
+            expr: None,
+        }
+    }
+
+    fn schema() -> SchemaGraph {
+        SchemaGraph::new(vec![
+            ObjectType {
+                name: "default::Named".into(),
+                is_abstract: true,
+                bases: vec!["std::Object".into()],
+                ancestors: vec!["std::Object".into(), "std::BaseObject".into()],
+                properties: vec![pointer("name", "std::str", true, false)],
+                links: vec![],
+            },
+            ObjectType {
+                name: "default::Post".into(),
+                is_abstract: false,
+                bases: vec!["std::Object".into()],
+                ancestors: vec!["std::Object".into(), "std::BaseObject".into()],
+                properties: vec![],
+                links: vec![pointer("author", "default::User", true, false)],
+            },
+            ObjectType {
+                name: "default::User".into(),
+                is_abstract: false,
+                bases: vec!["default::Named".into()],
+                ancestors: vec![
+                    "default::Named".into(),
+                    "std::Object".into(),
+                    "std::BaseObject".into(),
+                ],
+                properties: vec![
+                    pointer("name", "std::str", true, false),
+                    pointer("tags", "std::str", false, true),
+                ],
+                links: vec![
+                    pointer("friends", "default::User", false, true),
+                    Pointer {
+                        computed: true,
+                        expr: Some(".<author[IS default::Post]".into()),
+                        ..pointer("posts", "default::Post", false, true)
+                    },
+                ],
+            },
+        ])
+    }
+
+    #[test]
+    fn inherited_pointers() {
+        let graph = schema();
+        let user = &graph.types[2];
+        assert_eq!(user.properties.len(), 1);
+        assert_eq!(user.properties[0].name, "tags");
+    }
+
+    #[test]
+    fn backlink() {
+        let mut link = pointer("posts", "default::Post", false, true);
+        assert_eq!(link.backlink_of(), None);
+        link.expr = Some(" .< `author` [IS Post]".into());
+        assert_eq!(link.backlink_of(), Some("author"));
+        link.expr = Some(".author.posts".into());
+        assert_eq!(link.backlink_of(), None);
+    }
+
+    #[test]
+    fn mermaid() {
+        assert_eq!(
+            schema().to_mermaid(),
+            "classDiagram\n    \
+            class default__Named[\"default::Named\"]\n    \
+            <<abstract>> default__Named\n    \
+            default__Named : required name: str\n    \
+            class default__Post[\"default::Post\"]\n    \
+            class default__User[\"default::User\"]\n    \
+            default__User : multi tags: str\n    \
+            default__Post --> \"1\" default__User : author\n    \
+            default__Named <|-- default__User\n    \
+            default__User --> \"0..*\" default__User : friends\n    \
+            default__User ..> \"0..*\" default__Post : posts (backlink of author)\n"
+        );
+    }
+
+    #[test]
+    fn dot() {
+        assert_eq!(
+            schema().to_dot(),
+            "digraph schema {\n    \
+            rankdir=LR;\n    \
+            node [shape=record];\n    \
+            \"default::Named\" [label=\"{«abstract»\\ndefault::Named|required name: str\\l}\"];\n    \
+            \"default::Post\" [label=\"{default::Post|}\"];\n    \
+            \"default::User\" [label=\"{default::User|multi tags: str\\l}\"];\n    \
+            \"default::Post\" -> \"default::User\" [label=\"author 1\"];\n    \
+            \"default::User\" -> \"default::Named\" [arrowhead=empty];\n    \
+            \"default::User\" -> \"default::User\" [label=\"friends 0..*\"];\n    \
+            \"default::User\" -> \"default::Post\" \
+            [label=\"posts 0..* (backlink of author)\", style=dashed];\n\
+            }\n"
+        );
+    }
+
+    #[test]
+    fn external_targets() {
+        let mut graph = schema();
+        graph.types.retain(|t| t.name != "default::Post");
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.contains("    class default__Post[\"default::Post\"]\n"));
+        let dot = graph.to_dot();
+        assert!(dot.contains("    \"default::Post\" [label=\"default::Post\"];\n"));
+    }
+}
@@ -0,0 +1,475 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use gel_derive::Queryable;
+use serde::{Deserialize, Serialize};
+
+use crate::connect::Connection;
+
+/// Incremented on incompatible changes of the document structure
+pub const FORMAT_VERSION: u32 = 1;
+
+const INTROSPECTION_QUERY: &str = r###"
+    WITH
+        MODULE schema,
+        SYSTEM := "^(?:std|schema|math|sys|cfg|cal|stdgraphql)(?:::|$)",
+    SELECT <json>{
+        modules := array_agg((
+            SELECT Module FILTER NOT re_test(SYSTEM, .name) ORDER BY .name
+        ).name),
+        object_types := array_agg((
+            SELECT ObjectType {
+                name,
+                `abstract` := .is_abstract,
+                base_names := array_agg(.bases.name),
+                ancestor_names := array_agg(.ancestors.name),
+                pointers: {
+                    name,
+                    kind := (
+                        'link' IF .__type__.name = 'schema::Link'
+                        ELSE 'property'
+                    ),
+                    target_name := .target.name,
+                    required,
+                    readonly,
+                    cardinality_name := <str>.cardinality,
+                    expr,
+                    default,
+                    constraints: {
+                        name,
+                        arg_values := array_agg((
+                            SELECT .params { value := @value }
+                            FILTER .name != '__subject__'
+                            ORDER BY .num
+                        ).value),
+                        subjectexpr,
+                        except_expr,
+                        errmessage,
+                        delegated,
+                    } ORDER BY .name,
+                    annotations: {
+                        name,
+                        value := @value,
+                    } ORDER BY .name,
+                }
+                FILTER .name NOT IN {'id', '__type__'}
+                ORDER BY .name,
+                constraints: {
+                    name,
+                    arg_values := array_agg((
+                        SELECT .params { value := @value }
+                        FILTER .name != '__subject__'
+                        ORDER BY .num
+                    ).value),
+                    subjectexpr,
+                    except_expr,
+                    errmessage,
+                    delegated,
+                } ORDER BY .name,
+                indexes: {
+                    expr,
+                    except_expr,
+                    annotations: {
+                        name,
+                        value := @value,
+                    } ORDER BY .name,
+                } ORDER BY .expr,
+                annotations: {
+                    name,
+                    value := @value,
+                } ORDER BY .name,
+            }
+            FILTER NOT .is_compound_type AND NOT .is_from_alias
+                AND NOT re_test(SYSTEM, .name)
+            ORDER BY .name
+        )),
+        scalar_types := array_agg((
+            SELECT ScalarType {
+                name,
+                `abstract` := .is_abstract,
+                base_names := array_agg(.bases.name),
+                enum_values,
+                constraints: {
+                    name,
+                    arg_values := array_agg((
+                        SELECT .params { value := @value }
+                        FILTER .name != '__subject__'
+                        ORDER BY .num
+                    ).value),
+                    subjectexpr,
+                    except_expr,
+                    errmessage,
+                    delegated,
+                } ORDER BY .name,
+                annotations: {
+                    name,
+                    value := @value,
+                } ORDER BY .name,
+            }
+            FILTER NOT .is_from_alias AND NOT re_test(SYSTEM, .name)
+            ORDER BY .name
+        )),
+        functions := array_agg((
+            SELECT Function {
+                name,
+                params: {
+                    name,
+                    kind_name := <str>.kind,
+                    typemod_name := <str>.typemod,
+                    type_name := .type.name,
+                    default,
+                } ORDER BY .num,
+                return_type_name := .return_type.name,
+                return_typemod_name := <str>.return_typemod,
+                volatility_name := <str>.volatility,
+                body,
+                annotations: {
+                    name,
+                    value := @value,
+                } ORDER BY .name,
+            }
+            FILTER NOT re_test(SYSTEM, .name)
+            ORDER BY .name
+        )),
+        globals := array_agg((
+            SELECT Global {
+                name,
+                target_name := .target.name,
+                required,
+                cardinality_name := <str>.cardinality,
+                default,
+                expr,
+                annotations: {
+                    name,
+                    value := @value,
+                } ORDER BY .name,
+            }
+            FILTER NOT re_test(SYSTEM, .name)
+            ORDER BY .name
+        )),
+    }
+"###;
+
+#[derive(Deserialize, Queryable, Debug)]
+#[gel(json)]
+struct RawSchema {
+    modules: Vec<String>,
+    object_types: Vec<RawObjectType>,
+    scalar_types: Vec<ScalarType>,
+    functions: Vec<RawFunction>,
+    globals: Vec<RawGlobal>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawObjectType {
+    name: String,
+    #[serde(rename = "abstract")]
+    is_abstract: bool,
+    #[serde(rename = "base_names")]
+    bases: Vec<String>,
+    #[serde(rename = "ancestor_names")]
+    ancestors: Vec<String>,
+    pointers: Vec<RawPointer>,
+    constraints: Vec<Constraint>,
+    indexes: Vec<Index>,
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawPointer {
+    name: String,
+    kind: String,
+    target_name: Option<String>,
+    required: bool,
+    readonly: bool,
+    cardinality_name: String,
+    expr: Option<String>,
+    default: Option<String>,
+    constraints: Vec<Constraint>,
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawFunction {
+    name: String,
+    params: Vec<RawParameter>,
+    return_type_name: String,
+    return_typemod_name: String,
+    volatility_name: String,
+    body: Option<String>,
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawParameter {
+    name: String,
+    kind_name: String,
+    typemod_name: String,
+    type_name: String,
+    default: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawGlobal {
+    name: String,
+    target_name: String,
+    required: bool,
+    cardinality_name: String,
+    default: Option<String>,
+    expr: Option<String>,
+    annotations: Vec<Annotation>,
+}
+
+/// Top-level document of `describe schema --format=json`
+#[derive(Serialize, Debug)]
+pub struct Document {
+    pub version: u32,
+    pub modules: Vec<Module>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Module {
+    pub name: String,
+    pub object_types: Vec<ObjectType>,
+    pub scalar_types: Vec<ScalarType>,
+    pub functions: Vec<Function>,
+    pub globals: Vec<Global>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ObjectType {
+    pub name: String,
+    #[serde(rename = "abstract")]
+    pub is_abstract: bool,
+    pub bases: Vec<String>,
+    pub pointers: Vec<Pointer>,
+    pub constraints: Vec<Constraint>,
+    pub indexes: Vec<Index>,
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Pointer {
+    pub name: String,
+    /// `property` or `link`
+    pub kind: String,
+    pub target: Option<String>,
+    pub required: bool,
+    pub readonly: bool,
+    /// `One` or `Many`
+    pub cardinality: String,
+    /// Defined on one of the base types
+    pub inherited: bool,
+    /// Expression of a computed pointer
+    pub expr: Option<String>,
+    pub default: Option<String>,
+    pub constraints: Vec<Constraint>,
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ScalarType {
+    pub name: String,
+    #[serde(rename = "abstract")]
+    pub is_abstract: bool,
+    #[serde(rename(deserialize = "base_names"))]
+    pub bases: Vec<String>,
+    pub enum_values: Option<Vec<String>>,
+    pub constraints: Vec<Constraint>,
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Constraint {
+    pub name: String,
+    #[serde(rename(deserialize = "arg_values"))]
+    pub args: Vec<String>,
+    pub subjectexpr: Option<String>,
+    pub except_expr: Option<String>,
+    pub errmessage: Option<String>,
+    pub delegated: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Index {
+    pub expr: String,
+    pub except_expr: Option<String>,
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Annotation {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<Parameter>,
+    pub return_type: String,
+    /// `SingletonType`, `OptionalType` or `SetOfType`
+    pub return_typemod: String,
+    pub volatility: String,
+    pub body: Option<String>,
+    pub annotations: Vec<Annotation>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Parameter {
+    pub name: String,
+    /// `PositionalParam`, `NamedOnlyParam` or `VariadicParam`
+    pub kind: String,
+    pub typemod: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub default: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Global {
+    pub name: String,
+    pub target: String,
+    pub required: bool,
+    pub cardinality: String,
+    pub default: Option<String>,
+    pub expr: Option<String>,
+    pub annotations: Vec<Annotation>,
+}
+
+/// Fetches the schema, with modules limited to `modules` if it's not empty
+pub async fn fetch(cli: &mut Connection, modules: &[String]) -> anyhow::Result<Document> {
+    let raw = cli
+        .query_required_single::<RawSchema, _>(INTROSPECTION_QUERY, &())
+        .await?;
+    let document = Document::new(raw);
+    if let Some(missing) = modules
+        .iter()
+        .find(|name| !document.modules.iter().any(|m| &&m.name == name))
+    {
+        anyhow::bail!("module {missing:?} does not exist");
+    }
+    Ok(Document {
+        version: document.version,
+        modules: document
+            .modules
+            .into_iter()
+            .filter(|m| modules.is_empty() || modules.contains(&m.name))
+            .collect(),
+    })
+}
+
+/// Module part of a schema item name
+pub fn module_of(name: &str) -> &str {
+    name.rsplit_once("::")
+        .map(|(module, _)| module)
+        .unwrap_or("")
+}
+
+impl Document {
+    fn new(raw: RawSchema) -> Document {
+        let mut modules = BTreeMap::new();
+        for name in raw.modules {
+            Module::get(&mut modules, &name);
+        }
+
+        let owned: BTreeMap<String, BTreeSet<String>> = raw
+            .object_types
+            .iter()
+            .map(|t| {
+                let names = t.pointers.iter().map(|p| p.name.clone()).collect();
+                (t.name.clone(), names)
+            })
+            .collect();
+        for typ in raw.object_types {
+            let pointers = typ
+                .pointers
+                .into_iter()
+                .map(|p| Pointer {
+                    inherited: typ
+                        .ancestors
+                        .iter()
+                        .filter_map(|a| owned.get(a))
+                        .any(|names| names.contains(&p.name)),
+                    name: p.name,
+                    kind: p.kind,
+                    target: p.target_name,
+                    required: p.required,
+                    readonly: p.readonly,
+                    cardinality: p.cardinality_name,
+                    expr: p.expr,
+                    default: p.default,
+                    constraints: p.constraints,
+                    annotations: p.annotations,
+                })
+                .collect();
+            Module::get(&mut modules, module_of(&typ.name))
+                .object_types
+                .push(ObjectType {
+                    name: typ.name,
+                    is_abstract: typ.is_abstract,
+                    bases: typ.bases,
+                    pointers,
+                    constraints: typ.constraints,
+                    indexes: typ.indexes,
+                    annotations: typ.annotations,
+                });
+        }
+        for scalar in raw.scalar_types {
+            Module::get(&mut modules, module_of(&scalar.name))
+                .scalar_types
+                .push(scalar);
+        }
+        for func in raw.functions {
+            Module::get(&mut modules, module_of(&func.name))
+                .functions
+                .push(Function {
+                    params: func
+                        .params
+                        .into_iter()
+                        .map(|p| Parameter {
+                            name: p.name,
+                            kind: p.kind_name,
+                            typemod: p.typemod_name,
+                            type_name: p.type_name,
+                            default: p.default,
+                        })
+                        .collect(),
+                    name: func.name,
+                    return_type: func.return_type_name,
+                    return_typemod: func.return_typemod_name,
+                    volatility: func.volatility_name,
+                    body: func.body,
+                    annotations: func.annotations,
+                });
+        }
+        for global in raw.globals {
+            Module::get(&mut modules, module_of(&global.name))
+                .globals
+                .push(Global {
+                    name: global.name,
+                    target: global.target_name,
+                    required: global.required,
+                    cardinality: global.cardinality_name,
+                    default: global.default,
+                    expr: global.expr,
+                    annotations: global.annotations,
+                });
+        }
+        Document {
+            version: FORMAT_VERSION,
+            modules: modules.into_values().collect(),
+        }
+    }
+}
+
+impl Module {
+    fn get<'a>(modules: &'a mut BTreeMap<String, Module>, name: &str) -> &'a mut Module {
+        modules.entry(name.into()).or_insert_with(|| Module {
+            name: name.into(),
+            object_types: Vec::new(),
+            scalar_types: Vec::new(),
+            functions: Vec::new(),
+            globals: Vec::new(),
+        })
+    }
+}
@@ -1,4 +1,4 @@
-use std::borrow::Borrow;
+use std::borrow::{Borrow, Cow};
 use std::cmp::{Ordering, min};
 use std::collections::BTreeMap;
 use std::ops::Bound;
@@ -8,17 +8,13 @@ use edgeql_parser::preparser;
 
 use crate::commands::backslash;
 
+pub mod schema;
+
 /// Information about the current statement in the prompt.
 #[derive(Debug)]
 pub enum Current<'a> {
-    #[allow(dead_code)]
-    EdgeQL {
-        text: &'a str,
-        complete: bool,
-    },
-    Backslash {
-        text: &'a str,
-    },
+    EdgeQL { text: &'a str, complete: bool },
+    Backslash { text: &'a str },
     Empty,
 }
 
@@ -49,8 +45,8 @@ pub enum SettingValue {
 }
 
 pub struct Pair {
-    value: &'static str,
-    description: &'static str,
+    value: Cow<'static, str>,
+    description: Cow<'static, str>,
 }
 
 pub struct Hint {
@@ -118,10 +114,7 @@ fn complete_command(input: &str) -> Vec<Pair> {
         .top_commands
         .range_from(input)
         .filter(|x| x.starts_with(input))
-        .map(|x| Pair {
-            value: x,
-            description: x,
-        })
+        .map(|x| Pair::new(&x[..], &x[..]))
         .collect()
 }
 
@@ -130,10 +123,7 @@ fn complete_setting(input: &str) -> Vec<Pair> {
         .settings
         .range_from(input)
         .filter(|(name, _)| name.starts_with(input))
-        .map(|(name, setting)| Pair {
-            value: name,
-            description: &setting.name_description,
-        })
+        .map(|(name, setting)| Pair::new(*name, &setting.name_description[..]))
         .collect()
 }
 
@@ -143,10 +133,7 @@ fn complete_subcommand(
 ) -> Vec<Pair> {
     cmds.range_from(input)
         .filter(|(name, _)| name.starts_with(input))
-        .map(|(name, cmdinfo)| Pair {
-            value: name,
-            description: &cmdinfo.name_description,
-        })
+        .map(|(name, cmdinfo)| Pair::new(&name[..], &cmdinfo.name_description[..]))
         .collect()
 }
 
@@ -156,18 +143,23 @@ fn complete_setting_value(input: &str, val: &SettingValue) -> Vec<Pair> {
         SettingValue::Variants(v) => v
             .iter()
             .filter(|x| x.starts_with(input))
-            .map(|x| Pair {
-                value: x,
-                description: x,
-            })
+            .map(|x| Pair::new(&x[..], &x[..]))
             .collect(),
     }
 }
 
-pub fn complete(input: &str, cursor: usize) -> Option<(usize, Vec<Pair>)> {
+pub fn complete(
+    input: &str,
+    cursor: usize,
+    schema: Option<&schema::Schema>,
+) -> Option<(usize, Vec<Pair>)> {
     match current(input, cursor) {
         (_, Current::Empty) => None,
-        (_, Current::EdgeQL { .. }) => None,
+        (off, Current::EdgeQL { text, .. }) => {
+            let end = cursor.checked_sub(off)?.min(text.len());
+            let (start, pairs) = schema?.complete(&text[..end])?;
+            Some((off + start, pairs))
+        }
         (off, Current::Backslash { text: cmd }) => {
             use BackslashFsm as Fsm;
             use backslash::Item::*;
@@ -628,12 +620,21 @@ impl BackslashFsm {
     }
 }
 
+impl Pair {
+    fn new(value: impl Into<Cow<'static, str>>, description: impl Into<Cow<'static, str>>) -> Pair {
+        Pair {
+            value: value.into(),
+            description: description.into(),
+        }
+    }
+}
+
 impl rustyline::completion::Candidate for Pair {
     fn replacement(&self) -> &str {
-        self.value
+        &self.value
     }
     fn display(&self) -> &str {
-        self.description
+        &self.description
     }
 }
 
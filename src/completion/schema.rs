@@ -0,0 +1,492 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use edgeql_parser::keywords::Keyword;
+use edgeql_parser::tokenizer::{Kind, Token, Tokenizer};
+use gel_derive::Queryable;
+use serde::Deserialize;
+
+use crate::completion::Pair;
+use crate::connect::Connection;
+
+const DEFAULT_MODULE: &str = "default";
+const STD_MODULE: &str = "std";
+
+const INTROSPECTION_QUERY: &str = r###"
+    WITH MODULE schema
+    SELECT <json>{
+        modules := array_agg(Module.name),
+        object_types := array_agg((
+            SELECT ObjectType {
+                name,
+                pointers: {
+                    name,
+                    target_name := .target.name,
+                    is_link := .__type__.name = 'schema::Link',
+                },
+            }
+            FILTER NOT .is_compound_type AND NOT .is_from_alias
+        )),
+        scalar_types := array_agg(ScalarType.name),
+        functions := array_agg(DISTINCT Function.name),
+        globals := array_agg(Global.name),
+    }
+"###;
+
+#[derive(Deserialize, Queryable, Debug)]
+#[gel(json)]
+struct RawSchema {
+    modules: Vec<String>,
+    object_types: Vec<RawObjectType>,
+    scalar_types: Vec<String>,
+    functions: Vec<String>,
+    globals: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawObjectType {
+    name: String,
+    pointers: Vec<RawPointer>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawPointer {
+    name: String,
+    target_name: Option<String>,
+    is_link: bool,
+}
+
+/// Names introspected from the current branch, used for completion
+#[derive(Debug, Default)]
+pub struct Schema {
+    modules: BTreeSet<String>,
+    object_types: BTreeMap<String, ObjectType>,
+    scalar_types: BTreeSet<String>,
+    functions: BTreeSet<String>,
+    globals: BTreeSet<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct ObjectType {
+    pointers: BTreeMap<String, Pointer>,
+}
+
+#[derive(Debug)]
+pub struct Pointer {
+    target: Option<String>,
+    is_link: bool,
+}
+
+#[derive(Debug)]
+enum Bracket<'s> {
+    Shape(Option<&'s ObjectType>),
+    Other,
+}
+
+pub async fn fetch(cli: &mut Connection) -> anyhow::Result<Schema> {
+    let raw = cli
+        .query_required_single::<RawSchema, _>(INTROSPECTION_QUERY, &())
+        .await?;
+    Ok(Schema::from(raw))
+}
+
+impl From<RawSchema> for Schema {
+    fn from(raw: RawSchema) -> Schema {
+        Schema {
+            modules: raw.modules.into_iter().collect(),
+            object_types: raw
+                .object_types
+                .into_iter()
+                .map(|typ| {
+                    let pointers = typ
+                        .pointers
+                        .into_iter()
+                        .map(|p| {
+                            let ptr = Pointer {
+                                target: p.target_name,
+                                is_link: p.is_link,
+                            };
+                            (p.name, ptr)
+                        })
+                        .collect();
+                    (typ.name, ObjectType { pointers })
+                })
+                .collect(),
+            scalar_types: raw.scalar_types.into_iter().collect(),
+            functions: raw.functions.into_iter().collect(),
+            globals: raw.globals.into_iter().collect(),
+        }
+    }
+}
+
+fn is_word(tok: &Token, word: &'static str) -> bool {
+    match &tok.kind {
+        Kind::Keyword(Keyword(kw)) => *kw == word,
+        Kind::Ident => tok.text.eq_ignore_ascii_case(word),
+        _ => false,
+    }
+}
+
+fn is_name(tok: &Token) -> bool {
+    tok.kind == Kind::Ident
+}
+
+/// Module names such as `default` may be tokenized as keywords
+fn is_name_part(tok: &Token) -> bool {
+    matches!(tok.kind, Kind::Ident | Kind::Keyword(_))
+}
+
+/// Strips `module::` from names in the default and std modules, as those
+/// can be referred to unqualified
+fn short_name(name: &str) -> &str {
+    match name.split_once("::") {
+        Some((DEFAULT_MODULE | STD_MODULE, short)) if !short.contains("::") => short,
+        _ => name,
+    }
+}
+
+impl Schema {
+    #[cfg(test)]
+    fn add_type(&mut self, name: &str, pointers: &[(&str, Option<&str>)]) {
+        let pointers = pointers
+            .iter()
+            .map(|(name, target)| {
+                let ptr = Pointer {
+                    target: target.map(|t| t.to_owned()),
+                    is_link: target.is_some_and(|t| !t.starts_with("std::")),
+                };
+                (name.to_string(), ptr)
+            })
+            .collect();
+        self.modules.insert(name.split("::").next().unwrap().into());
+        self.object_types
+            .insert(name.into(), ObjectType { pointers });
+    }
+
+    fn resolve_type(&self, name: &str) -> Option<&ObjectType> {
+        if name.contains("::") {
+            return self.object_types.get(name);
+        }
+        self.object_types
+            .get(&format!("{DEFAULT_MODULE}::{name}"))
+            .or_else(|| self.object_types.get(&format!("{STD_MODULE}::{name}")))
+    }
+
+    fn target_of<'s>(&'s self, typ: &'s ObjectType, pointer: &str) -> Option<&'s ObjectType> {
+        typ.pointers
+            .get(pointer)
+            .and_then(|p| p.target.as_deref())
+            .and_then(|t| self.object_types.get(t))
+    }
+
+    /// Reads a (possibly qualified) name ending at `tokens[end - 1]`,
+    /// returns the name and the index of its first token
+    fn qualified_name(tokens: &[Token], end: usize) -> Option<(String, usize)> {
+        let mut start = end.checked_sub(1)?;
+        if !is_name_part(&tokens[start]) {
+            return None;
+        }
+        while start >= 2
+            && tokens[start - 1].kind == Kind::Namespace
+            && is_name_part(&tokens[start - 2])
+        {
+            start -= 2;
+        }
+        let name = tokens[start..end]
+            .iter()
+            .filter(|t| t.kind != Kind::Namespace)
+            .map(|t| &t.text[..])
+            .collect::<Vec<_>>()
+            .join("::");
+        Some((name, start))
+    }
+
+    /// Resolves the type of a path like `User.friends` or `.friends`
+    /// ending at `tokens[end - 1]`
+    fn path_type<'s>(
+        &'s self,
+        tokens: &[Token],
+        end: usize,
+        subject: Option<&'s ObjectType>,
+    ) -> Option<&'s ObjectType> {
+        let mut steps = Vec::new();
+        let mut idx = end;
+        loop {
+            let Some(name_idx) = idx.checked_sub(1) else {
+                break;
+            };
+            if !is_name(&tokens[name_idx]) {
+                break;
+            }
+            if name_idx >= 1 && tokens[name_idx - 1].kind == Kind::Dot {
+                steps.push(&tokens[name_idx].text[..]);
+                idx = name_idx - 1;
+            } else {
+                let (name, _) = Self::qualified_name(tokens, idx)?;
+                return steps
+                    .iter()
+                    .rev()
+                    .try_fold(self.resolve_type(&name)?, |typ, step| {
+                        self.target_of(typ, step)
+                    });
+            }
+        }
+        steps
+            .iter()
+            .rev()
+            .try_fold(subject?, |typ, step| self.target_of(typ, step))
+    }
+
+    fn pointers(&self, typ: &ObjectType, prefix: &str) -> Vec<Pair> {
+        typ.pointers
+            .iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .map(|(name, ptr)| {
+                let target = ptr.target.as_deref().unwrap_or("anytype");
+                let kind = if ptr.is_link { "link" } else { "property" };
+                Pair::new(name.clone(), format!("{name} -- {kind} {target}"))
+            })
+            .collect()
+    }
+
+    fn module_members(&self, module: &str, prefix: &str) -> Vec<Pair> {
+        let names = self
+            .object_types
+            .keys()
+            .map(|n| (n, "object type"))
+            .chain(self.scalar_types.iter().map(|n| (n, "scalar type")))
+            .chain(self.functions.iter().map(|n| (n, "function")))
+            .chain(self.globals.iter().map(|n| (n, "global")));
+        let mut result = BTreeMap::new();
+        for (name, kind) in names {
+            let Some(short) = name.strip_prefix(module).and_then(|n| n.strip_prefix("::")) else {
+                continue;
+            };
+            if short.starts_with(prefix) && !short.contains("::") {
+                result
+                    .entry(short.to_owned())
+                    .or_insert_with(|| format!("{short} -- {kind}"));
+            }
+        }
+        result
+            .into_iter()
+            .map(|(value, descr)| Pair::new(value, descr))
+            .collect()
+    }
+
+    fn globals(&self, prefix: &str) -> Vec<Pair> {
+        self.globals
+            .iter()
+            .map(|name| short_name(name))
+            .filter(|name| name.starts_with(prefix))
+            .map(|name| Pair::new(name.to_owned(), format!("{name} -- global")))
+            .collect()
+    }
+
+    fn toplevel(&self, prefix: &str) -> Vec<Pair> {
+        let names = self
+            .object_types
+            .keys()
+            .map(|n| (n, "object type"))
+            .chain(self.scalar_types.iter().map(|n| (n, "scalar type")))
+            .chain(self.functions.iter().map(|n| (n, "function")));
+        let mut result = BTreeMap::new();
+        for (name, kind) in names {
+            let name = short_name(name);
+            if name.starts_with(prefix) && !name.contains("::") {
+                result
+                    .entry(name.to_owned())
+                    .or_insert_with(|| format!("{name} -- {kind}"));
+            }
+        }
+        for module in &self.modules {
+            if module.starts_with(prefix) && !module.contains("::") {
+                result
+                    .entry(format!("{module}::"))
+                    .or_insert_with(|| format!("{module}:: -- module"));
+            }
+        }
+        result
+            .into_iter()
+            .map(|(value, descr)| Pair::new(value, descr))
+            .collect()
+    }
+
+    /// Completes EdgeQL text at the end of `text`
+    ///
+    /// Returns the offset of the replaced identifier within `text` and the
+    /// list of candidates.
+    pub fn complete(&self, text: &str) -> Option<(usize, Vec<Pair>)> {
+        let mut tokens = Vec::new();
+        for tok in Tokenizer::new(text) {
+            // Unterminated strings and similar: nothing sensible to offer
+            tokens.push(tok.ok()?);
+        }
+        let (prefix, start) = match tokens.last() {
+            Some(tok) if is_name_part(tok) && tok.span.end as usize == text.len() => {
+                let tok = tokens.pop().expect("checked above");
+                (tok.text.into_owned(), tok.span.start as usize)
+            }
+            _ => (String::new(), text.len()),
+        };
+
+        let mut brackets = Vec::new();
+        let mut subject = None;
+        for (idx, tok) in tokens.iter().enumerate() {
+            match tok.kind {
+                Kind::OpenBrace => {
+                    let owner = if idx >= 1 && tokens[idx - 1].kind == Kind::Colon {
+                        let parent = match brackets.last() {
+                            Some(Bracket::Shape(parent)) => *parent,
+                            _ => None,
+                        };
+                        idx.checked_sub(2)
+                            .filter(|i| is_name(&tokens[*i]))
+                            .zip(parent)
+                            .and_then(|(i, parent)| self.target_of(parent, &tokens[i].text))
+                    } else if idx >= 1 && is_word(&tokens[idx - 1], "set") {
+                        // `update Type set { ... }`
+                        subject
+                    } else if idx >= 1 && is_name(&tokens[idx - 1]) {
+                        self.path_type(&tokens, idx, subject)
+                    } else {
+                        None
+                    };
+                    brackets.push(Bracket::Shape(owner));
+                }
+                Kind::OpenParen | Kind::OpenBracket => brackets.push(Bracket::Other),
+                Kind::CloseBrace | Kind::CloseParen | Kind::CloseBracket => {
+                    brackets.pop();
+                }
+                Kind::Ident => {
+                    if let Some((name, _)) = Self::qualified_name(&tokens, idx + 1) {
+                        if let Some(typ) = self.resolve_type(&name) {
+                            subject = Some(typ);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        let shape = match brackets.last() {
+            Some(Bracket::Shape(typ)) => *typ,
+            _ => None,
+        };
+
+        let candidates = match tokens.last().map(|t| &t.kind) {
+            Some(Kind::Namespace) => {
+                let (module, _) = Self::qualified_name(&tokens, tokens.len() - 1)?;
+                self.module_members(&module, &prefix)
+            }
+            Some(Kind::Dot) => {
+                let typ = self.path_type(&tokens, tokens.len() - 1, shape.or(subject))?;
+                self.pointers(typ, &prefix)
+            }
+            Some(Kind::OpenBrace | Kind::Comma) if shape.is_some() => {
+                self.pointers(shape.expect("checked above"), &prefix)
+            }
+            Some(_) if is_word(tokens.last().expect("checked above"), "global") => {
+                self.globals(&prefix)
+            }
+            _ => self.toplevel(&prefix),
+        };
+        Some((start, candidates))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Schema;
+
+    fn schema() -> Schema {
+        let mut schema = Schema::default();
+        schema.add_type(
+            "default::User",
+            &[
+                ("id", Some("std::uuid")),
+                ("name", Some("std::str")),
+                ("nickname", Some("std::str")),
+                ("friends", Some("default::User")),
+                ("posts", Some("default::Post")),
+            ],
+        );
+        schema.add_type(
+            "default::Post",
+            &[
+                ("title", Some("std::str")),
+                ("author", Some("default::User")),
+            ],
+        );
+        schema.add_type("blog::Comment", &[("body", Some("std::str"))]);
+        schema.functions.insert("std::len".into());
+        schema.globals.insert("default::current_user".into());
+        schema
+    }
+
+    fn complete(text: &str) -> Option<(usize, Vec<String>)> {
+        schema().complete(text).map(|(offset, pairs)| {
+            (
+                offset,
+                pairs.into_iter().map(|p| p.value.into_owned()).collect(),
+            )
+        })
+    }
+
+    #[test]
+    fn shape_pointers() {
+        assert_eq!(
+            complete("select User { na"),
+            Some((14, vec!["name".into()]))
+        );
+        assert_eq!(
+            complete("select User { name, n"),
+            Some((20, vec!["name".into(), "nickname".into()]))
+        );
+        assert_eq!(
+            complete("select default::Post { "),
+            Some((23, vec!["author".into(), "title".into()]))
+        );
+    }
+
+    #[test]
+    fn nested_shape() {
+        assert_eq!(
+            complete("select User { friends: { posts: { ti"),
+            Some((34, vec!["title".into()]))
+        );
+    }
+
+    #[test]
+    fn paths() {
+        assert_eq!(
+            complete("select Post.author.fr"),
+            Some((19, vec!["friends".into()]))
+        );
+        assert_eq!(
+            complete("select User filter .nick"),
+            Some((20, vec!["nickname".into()]))
+        );
+    }
+
+    #[test]
+    fn module_members() {
+        assert_eq!(
+            complete("select blog::Co"),
+            Some((13, vec!["Comment".into()]))
+        );
+    }
+
+    #[test]
+    fn toplevel() {
+        assert_eq!(complete("select P"), Some((7, vec!["Post".into()])));
+        assert_eq!(complete("select bl"), Some((7, vec!["blog::".into()])));
+        assert_eq!(
+            complete("select global cur"),
+            Some((14, vec!["current_user".into()]))
+        );
+    }
+
+    #[test]
+    fn unterminated_string() {
+        assert!(complete("select 'abc").is_none());
+    }
+}
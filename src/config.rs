@@ -42,6 +42,8 @@ pub struct ShellConfig {
     #[serde(default)]
     pub print_stats: Option<repl::PrintStats>,
     #[serde(default)]
+    pub csv_flatten: Option<repl::CsvFlatten>,
+    #[serde(default)]
     pub verbose_errors: Option<bool>,
 }
 
@@ -127,6 +129,7 @@ verbose-errors = false
                 sql_output_format: None,
                 display_typenames: None,
                 print_stats: Some(repl::PrintStats::Off),
+                csv_flatten: None,
                 verbose_errors: Some(false),
                 input_language: None,
             }
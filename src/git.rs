@@ -1,8 +1,15 @@
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use anyhow::Context;
 use gel_cli_instance::{ProcessError, ProcessErrorType, Processes, SystemProcessRunner};
 use log::warn;
 
+use crate::branding::BRANDING_CLI_CMD;
+
+const HOOK_MARKER: &str = "# installed by `project init --git-hook`";
+
 /// Get the current git branch.
 ///
 /// Returns `None` if git is not installed. Returns `None` if the current branch
@@ -37,3 +44,65 @@ pub async fn git_current_branch() -> Option<String> {
         }
     }
 }
+
+/// Lists local git branches of the repository in the current directory.
+pub async fn git_branches() -> anyhow::Result<Vec<String>> {
+    let mut cmd = Command::new("git");
+    cmd.args(["branch", "--format=%(refname:short)"]);
+    let output = Processes::new(SystemProcessRunner)
+        .run_string(cmd)
+        .await
+        .context("cannot list git branches")?;
+    Ok(output
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// Installs a `post-checkout` git hook that keeps the current branch in sync
+/// with the git branch.
+///
+/// Does nothing if the hook is already installed, and refuses to overwrite a
+/// `post-checkout` hook that was not installed by us.
+pub fn install_post_checkout_hook(project_dir: &Path) -> anyhow::Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .current_dir(project_dir)
+        .output()
+        .context("cannot run git")?;
+    if !output.status.success() {
+        anyhow::bail!("{} is not in a git repository", project_dir.display());
+    }
+    let hooks_dir = project_dir.join(String::from_utf8(output.stdout)?.trim());
+    let path = hooks_dir.join("post-checkout");
+
+    match fs::read_to_string(&path) {
+        Ok(text) if text.contains(HOOK_MARKER) => return Ok(path),
+        Ok(_) => anyhow::bail!(
+            "git hook {} already exists, add `{BRANDING_CLI_CMD} branch sync-git` to it manually",
+            path.display()
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e).context(format!("cannot read {}", path.display())),
+    }
+
+    fs::create_dir_all(&hooks_dir)?;
+    fs::write(
+        &path,
+        format!(
+            "#!/bin/sh\n\
+             {HOOK_MARKER}\n\
+             # the third argument is 1 for branch checkouts and 0 for file checkouts\n\
+             [ \"$3\" = \"1\" ] || exit 0\n\
+             exec {BRANDING_CLI_CMD} branch sync-git\n"
+        ),
+    )
+    .with_context(|| format!("cannot write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, PermissionsExt::from_mode(0o755))?;
+    }
+    Ok(path)
+}
@@ -26,7 +26,7 @@ use crate::commands::{ExitCode, backslash};
 use crate::config::Config;
 use crate::interrupt::{Interrupt, InterruptError};
 use crate::options::Options;
-use crate::outputs::tab_separated;
+use crate::outputs::{csv, tab_separated};
 use crate::print::Highlight;
 use crate::print::{self, PrintError, msg};
 use crate::prompt;
@@ -119,6 +119,7 @@ pub fn main(options: Options, cfg: Config) -> Result<(), anyhow::Error> {
         verbose_errors: cfg.shell.verbose_errors.unwrap_or(false),
         last_error: None,
         last_analyze: None,
+        last_query: None,
         implicit_limit,
         idle_transaction_timeout: idle_tx_timeout,
         input_language: options
@@ -136,6 +137,7 @@ pub fn main(options: Options, cfg: Config) -> Result<(), anyhow::Error> {
         display_typenames: cfg.shell.display_typenames.unwrap_or(true),
         input_mode: cfg.shell.input_mode.unwrap_or(repl::InputMode::Emacs),
         print_stats: cfg.shell.print_stats.unwrap_or(repl::PrintStats::Off),
+        csv_flatten: cfg.shell.csv_flatten.unwrap_or(repl::CsvFlatten::Json),
         history_limit: cfg.shell.history_size.unwrap_or(10000),
         branch: conn_config.db.clone(),
         conn_params: conn,
@@ -145,6 +147,9 @@ pub fn main(options: Options, cfg: Config) -> Result<(), anyhow::Error> {
         edgeql_state_desc: RawTypedesc::uninitialized(),
         edgeql_state: State::empty(),
         current_branch: None,
+        schema: None,
+        schema_outdated: true,
+        output_redirect: None,
     };
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
@@ -224,11 +229,45 @@ fn check_json_limit(json: &serde_json::Value, path: &str, limit: usize) -> bool
     true
 }
 
+struct Watch {
+    query: String,
+    interval: std::time::Duration,
+}
+
+struct Script {
+    name: String,
+    text: String,
+}
+
+/// Work that a backslash command leaves for the main loop to do
+enum Followup {
+    Watch(Watch),
+    Script(Script),
+}
+
+/// Text a statement was taken from, so that errors point at the right line
+#[derive(Clone, Copy)]
+struct Source<'a> {
+    name: &'a str,
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Source<'a> {
+    fn query(statement: &'a str) -> Source<'a> {
+        Source {
+            name: "<query>",
+            text: statement,
+            offset: 0,
+        }
+    }
+}
+
 async fn execute_backslash(
     state: &mut repl::State,
     text: &str,
     options: &Options,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<Option<Followup>> {
     use backslash::ExecuteResult::*;
 
     let cmd = match backslash::parse(text) {
@@ -241,7 +280,7 @@ async fn execute_backslash(
             }
             // Quick-edit command on error
             state.initial_text = text.into();
-            return Ok(());
+            return Ok(None);
         }
     };
     let res = Box::pin(backslash::execute(&cmd.command, state, options)).await;
@@ -252,6 +291,10 @@ async fn execute_backslash(
             return Err(CleanShutdown)?;
         }
         Ok(Input(text)) => state.initial_text = text,
+        Ok(WatchQuery { query, interval }) => {
+            return Ok(Some(Followup::Watch(Watch { query, interval })));
+        }
+        Ok(Script { name, text }) => return Ok(Some(Followup::Script(Script { name, text }))),
         Err(e) => {
             if e.is::<ExitCode>() {
                 // It's expected that command already printed all required
@@ -264,6 +307,96 @@ async fn execute_backslash(
             }
         }
     }
+    Ok(None)
+}
+
+async fn watch_query(
+    options: &Options,
+    state: &mut repl::State,
+    ctrlc: &Interrupt,
+    watch: &Watch,
+) -> anyhow::Result<()> {
+    let is_terminal = std::io::stdout().is_terminal();
+    loop {
+        if is_terminal {
+            write_out(&format!(
+                "{}{}",
+                anes::ClearBuffer::All,
+                anes::MoveCursorTo(1, 1)
+            ))
+            .await?;
+        }
+        msg!(
+            "{}",
+            format!(
+                "Every {}: {}  ({})",
+                humantime::format_duration(watch.interval),
+                watch.query.trim(),
+                humantime::format_rfc3339_seconds(std::time::SystemTime::now()),
+            )
+            .muted()
+        );
+        tokio::select!(
+            r = state.soft_reconnect() => r,
+            r = ctrlc.wait_result() => r,
+        )?;
+        tokio::select!(
+            r = execute_query(options, state, &watch.query, Source::query(&watch.query)) => r,
+            r = ctrlc.wait_result() => r,
+        )?;
+        state.read_state();
+        // Interrupt while waiting is a normal way to stop watching
+        tokio::select!(
+            _ = tokio::time::sleep(watch.interval) => {}
+            _ = ctrlc.wait() => return Ok(()),
+        );
+    }
+}
+
+async fn execute_script(
+    options: &Options,
+    state: &mut repl::State,
+    ctrlc: &Interrupt,
+    script: &Script,
+) -> anyhow::Result<()> {
+    let mut offset = 0;
+    while offset < script.text.len() {
+        let tail = &script.text[offset..];
+        let len = full_statement(tail.as_bytes(), None).unwrap_or(tail.len());
+        let statement = &tail[..len];
+        let source = Source {
+            name: &script.name,
+            text: &script.text,
+            offset,
+        };
+        offset += len;
+        if preparser::is_empty(statement) {
+            continue;
+        }
+        loop {
+            tokio::select!(
+                r = state.soft_reconnect() => r,
+                r = ctrlc.wait_result() => r,
+            )?;
+            let result = if classify::is_analyze(statement) {
+                tokio::select!(
+                    r = analyze::interactive(state, statement) => r,
+                    r = ctrlc.wait_result() => r,
+                )
+            } else {
+                tokio::select!(
+                    r = execute_query(options, state, statement, source) => r,
+                    r = ctrlc.wait_result() => r,
+                )
+            };
+            match result {
+                Err(e) if e.is::<RetryStateError>() && state.try_update_state()? => continue,
+                result => result?,
+            }
+            break;
+        }
+        state.read_state();
+    }
     Ok(())
 }
 
@@ -274,10 +407,30 @@ async fn write_out(data: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn write_result(
+    redirect: Option<&mut repl::OutputRedirect>,
+    data: &str,
+) -> anyhow::Result<()> {
+    let Some(redirect) = redirect else {
+        return write_out(data).await;
+    };
+    redirect
+        .file
+        .write_all(data.as_bytes())
+        .await
+        .with_context(|| format!("cannot write to {:?}", redirect.path))?;
+    redirect.file.flush().await?;
+    if redirect.tee {
+        write_out(data).await?;
+    }
+    Ok(())
+}
+
 async fn execute_query(
     options: &Options,
     state: &mut repl::State,
     statement: &str,
+    source: Source<'_>,
 ) -> anyhow::Result<()> {
     use crate::repl::OutputFormat::*;
     use crate::repl::PrintStats::*;
@@ -352,7 +505,13 @@ async fn execute_query(
                         return Err(RetryStateError)?;
                     }
                     Err(e) => {
-                        print::query_error(&e, statement, state.verbose_errors, "<query>")?;
+                        print::query_error_at(
+                            &e,
+                            source.text,
+                            source.offset,
+                            state.verbose_errors,
+                            source.name,
+                        )?;
                         return Err(QueryError)?;
                     }
                 }
@@ -362,7 +521,13 @@ async fn execute_query(
                 cli.clear_watch_error().await;
             }
             Err(e) => {
-                print::query_error(&e, statement, state.verbose_errors, "<query>")?;
+                print::query_error_at(
+                    &e,
+                    source.text,
+                    source.offset,
+                    state.verbose_errors,
+                    source.name,
+                )?;
                 return Err(QueryError)?;
             }
         }
@@ -372,7 +537,12 @@ async fn execute_query(
 
     if !items.can_contain_data() {
         match items.complete().await {
-            Ok(res) => print::completion(&res.status),
+            Ok(res) => {
+                if classify::is_schema_change(&res.status) {
+                    state.schema_outdated = true;
+                }
+                print::completion(&res.status)
+            }
             Err(e) if e.is::<StateMismatchError>() => {
                 return Err(RetryStateError)?;
             }
@@ -390,6 +560,10 @@ async fn execute_query(
         // update max_width each time
         cfg.max_width(w.into());
     }
+    if state.output_redirect.is_some() {
+        // escape codes are only useful on terminal
+        cfg.colors(false);
+    }
     match output_format {
         TabSeparated => {
             let mut index = 0;
@@ -421,10 +595,67 @@ async fn execute_query(
                 };
                 // trying to make writes atomic if possible
                 text += "\n";
-                write_out(&text).await?;
+                write_result(state.output_redirect.as_mut(), &text).await?;
                 index += 1;
             }
         }
+        Csv => {
+            let mut index = 0;
+            while let Some(row) = items.next().await.transpose()? {
+                if index == 0 && state.print_stats == Detailed {
+                    eprintln!("{}", format!("First row: {:?}", start.elapsed()).muted());
+                }
+                if let Some(limit) = state.implicit_limit {
+                    if index >= limit {
+                        eprintln!(
+                            "Error: Too many rows. Consider \
+                            adding an explicit `limit` clause, \
+                            or increasing the implicit limit \
+                            using `\\set limit`."
+                        );
+                        items.complete().await?;
+                        return Err(QueryError)?;
+                    }
+                }
+                let mut text = String::new();
+                if index == 0 {
+                    if let Some(header) = csv::format_header(&row) {
+                        text += &header;
+                        text += "\r\n";
+                    }
+                }
+                match csv::format_row(&row, state.csv_flatten) {
+                    Ok(row) => text += &row,
+                    Err(e) => {
+                        eprintln!("Error: {e}");
+                        // exhaust the iterator to get connection in the
+                        // consistent state
+                        items.complete().await?;
+                        return Err(QueryError)?;
+                    }
+                };
+                text += "\r\n";
+                write_result(state.output_redirect.as_mut(), &text).await?;
+                index += 1;
+            }
+        }
+        Tabular if state.output_redirect.is_some() => {
+            let text = match print::table_to_string(&mut items, &cfg).await {
+                Ok(text) => text,
+                Err(e) => {
+                    print::query_error_at(
+                        &e,
+                        source.text,
+                        source.offset,
+                        state.verbose_errors,
+                        source.name,
+                    )?;
+                    state.last_error = Some(e.into());
+                    return Err(QueryError)?;
+                }
+            };
+            write_result(state.output_redirect.as_mut(), &text).await?;
+        }
         Tabular => {
             match print::table_to_stdout(&mut items, &cfg).await {
                 Ok(()) => {}
@@ -433,7 +664,13 @@ async fn execute_query(
                         PrintError::StreamErr {
                             source: ref error, ..
                         } => {
-                            print::query_error(error, statement, state.verbose_errors, "<query>")?;
+                            print::query_error_at(
+                                error,
+                                source.text,
+                                source.offset,
+                                state.verbose_errors,
+                                source.name,
+                            )?;
                         }
                         _ => eprintln!("{e:#?}"),
                     }
@@ -443,6 +680,24 @@ async fn execute_query(
             }
             return Err(QueryError)?;
         }
+        Default if state.output_redirect.is_some() => {
+            let mut text = match print::native_to_string(&mut items, &cfg).await {
+                Ok(text) => text,
+                Err(e) => {
+                    print::query_error_at(
+                        &e,
+                        source.text,
+                        source.offset,
+                        state.verbose_errors,
+                        source.name,
+                    )?;
+                    state.last_error = Some(e.into());
+                    return Err(QueryError)?;
+                }
+            };
+            text += "\n";
+            write_result(state.output_redirect.as_mut(), &text).await?;
+        }
         Default => {
             match print::native_to_stdout(&mut items, &cfg).await {
                 Ok(()) => {}
@@ -451,7 +706,13 @@ async fn execute_query(
                         PrintError::StreamErr {
                             source: ref error, ..
                         } => {
-                            print::query_error(error, statement, state.verbose_errors, "<query>")?;
+                            print::query_error_at(
+                                error,
+                                source.text,
+                                source.offset,
+                                state.verbose_errors,
+                                source.name,
+                            )?;
                         }
                         _ => eprintln!("{e:#?}"),
                     }
@@ -494,7 +755,7 @@ async fn execute_query(
                 // trying to make writes atomic if possible
                 let mut data = print::json_to_string(jitems, &cfg)?;
                 data += "\n";
-                write_out(&data).await?;
+                write_result(state.output_redirect.as_mut(), &data).await?;
             }
         }
         JsonPretty | JsonLines => {
@@ -529,13 +790,13 @@ async fn execute_query(
                 if state.output_format == JsonLines {
                     // trying to make writes atomic if possible
                     text += "\n";
-                    write_out(&text).await?;
+                    write_result(state.output_redirect.as_mut(), &text).await?;
                 } else {
                     // trying to make writes atomic if possible
                     let mut data;
                     data = print::json_item_to_string(&value, &cfg)?;
                     data += "\n";
-                    write_out(&data).await?;
+                    write_result(state.output_redirect.as_mut(), &data).await?;
                     index += 1;
                 }
             }
@@ -568,6 +829,10 @@ async fn _interactive_main(
             _ = state.ensure_connection() => {}
             res = ctrlc.wait_result() => res?,
         );
+        tokio::select!(
+            res = state.refresh_schema() => res?,
+            res = ctrlc.wait_result() => res?,
+        );
         let cur_initial = std::mem::take(&mut state.initial_text);
         let inp = match state.edgeql_input(&cur_initial).await? {
             prompt::Input::Eof => {
@@ -586,10 +851,20 @@ async fn _interactive_main(
             'retry: loop {
                 let result = match item {
                     ToDoItem::Backslash(text) => {
-                        tokio::select!(
+                        let res = tokio::select!(
                             res = execute_backslash(state, text, options) => res,
                             res = ctrlc.wait_result() => res,
-                        )
+                        );
+                        match res {
+                            Ok(Some(Followup::Watch(watch))) => {
+                                Box::pin(watch_query(options, state, &ctrlc, &watch)).await
+                            }
+                            Ok(Some(Followup::Script(script))) => {
+                                Box::pin(execute_script(options, state, &ctrlc, &script)).await
+                            }
+                            Ok(None) => Ok(()),
+                            Err(e) => Err(e),
+                        }
                     }
                     ToDoItem::Explain(statement) => tokio::select!(
                         r = state.soft_reconnect() => r,
@@ -599,14 +874,17 @@ async fn _interactive_main(
                         r = analyze::interactive(state, statement) => r,
                         r = ctrlc.wait_result() => r,
                     )),
-                    ToDoItem::Query(statement) => tokio::select!(
-                        r = state.soft_reconnect() => r,
-                        r = ctrlc.wait_result() => r,
-                    )
-                    .and(tokio::select!(
-                        r = execute_query(options, state, statement) => r,
-                        r = ctrlc.wait_result() => r,
-                    )),
+                    ToDoItem::Query(statement) => {
+                        state.last_query = Some(statement.to_owned());
+                        tokio::select!(
+                            r = state.soft_reconnect() => r,
+                            r = ctrlc.wait_result() => r,
+                        )
+                        .and(tokio::select!(
+                            r = execute_query(options, state, statement, Source::query(statement)) => r,
+                            r = ctrlc.wait_result() => r,
+                        ))
+                    }
                 };
                 if let Err(err) = result {
                     if err.is::<InterruptError>() {
@@ -73,6 +73,11 @@ pub struct Command {
     #[arg(long)]
     pub dev_mode: bool,
 
+    /// Apply dev mode migrations even if the branch is protected in the
+    /// project manifest.
+    #[arg(long, requires = "dev_mode")]
+    pub force_protected: bool,
+
     /// Disable building of concurrent indexes after applying migrations.
     ///
     /// Indexes that set `build_concurrently` to `true` are not built when migration is applied.
@@ -106,6 +111,10 @@ pub async fn run_inner(
     skip_auto_backup: bool,
 ) -> Result<(), anyhow::Error> {
     if cmd.dev_mode {
+        if let Some(project) = &ctx.project {
+            let branch = conn.get_current_branch().await?.to_string();
+            project.check_protected_branch(&branch, cmd.force_protected)?;
+        }
         let bar = if cmd.quiet {
             ProgressBar::hidden()
         } else {
@@ -1043,6 +1052,38 @@ mod test {
         );
     }
 
+    #[test]
+    fn partial_squash() {
+        // m103 and m104 are squashed into m113, m105 is rewritten as m115
+        let migrations = indexmap! {
+            "m101".into() => normal("m101", "initial"),
+            "m102".into() => normal("m102", "m101"),
+            "m113".into() => normal("m113", "m102"),
+            "m115".into() => normal("m115", "m113"),
+        };
+        let fixups = vec![
+            fixup("m113", "m103"),
+            fixup("m113", "m104"),
+            fixup("m115", "m105"),
+        ];
+        assert_eq!(
+            find_path(&migrations, &fixups, "m102", "m115"),
+            vec![Normal("m113"), Normal("m115")],
+        );
+        assert_eq!(
+            find_path(&migrations, &fixups, "m103", "m115"),
+            vec![Fixup("m113"), Normal("m115")],
+        );
+        assert_eq!(
+            find_path(&migrations, &fixups, "m104", "m115"),
+            vec![Fixup("m113"), Normal("m115")],
+        );
+        assert_eq!(
+            find_path(&migrations, &fixups, "m105", "m115"),
+            vec![Fixup("m115")],
+        );
+    }
+
     #[test]
     fn shortcut() {
         // I'm not sure it's useful, but the test here is to ensure that this
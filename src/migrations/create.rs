@@ -40,7 +40,7 @@ use crate::print::style::Styler;
 use crate::print::{self, AsRelativeToCurrentDir, Highlight};
 use crate::question;
 
-const SAFE_CONFIDENCE: f64 = 0.99999;
+pub const SAFE_CONFIDENCE: f64 = 0.99999;
 
 pub async fn run(cmd: &Command, conn: &mut Connection, options: &Options) -> anyhow::Result<()> {
     if cmd.squash {
@@ -60,6 +60,18 @@ pub struct Command {
     /// Note: this discards data migrations.
     #[arg(long)]
     pub squash: bool,
+    /// Squash only the migrations after this revision, keeping it and the
+    /// migrations before it untouched. Fixups are created for databases
+    /// at any of the squashed revisions.
+    ///
+    /// A unique revision prefix can be specified instead of a full
+    /// revision name.
+    #[arg(long, value_name = "REVISION", requires = "squash")]
+    pub from: Option<String>,
+    /// Squash only the migrations up to (and including) this revision.
+    /// Later migrations are kept, but rewritten on top of the squashed one.
+    #[arg(long, value_name = "REVISION", requires = "squash")]
+    pub to: Option<String>,
     /// Do not ask questions. By default works only if "safe" changes are
     /// to be done (those for which [`BRANDING`] has a high degree of confidence).
     /// This safe default can be overridden with `--allow-unsafe`.
@@ -159,8 +171,8 @@ enum Choice {
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct RequiredUserInput {
-    placeholder: String,
-    prompt: String,
+    pub placeholder: String,
+    pub prompt: String,
     #[allow(dead_code)]
     old_type: Option<String>,
     old_type_is_object: Option<bool>,
@@ -239,14 +251,14 @@ struct SplitMigration;
     "{BRANDING} could not resolve migration automatically. \
          Please run `{BRANDING_CLI_CMD} migration create` in interactive mode."
 )]
-struct CantResolve;
+pub struct CantResolve;
 
 #[derive(Debug, thiserror::Error)]
 #[error("cannot proceed until schema files are fixed")]
 pub struct SchemaFileError;
 
 impl FutureMigration {
-    fn new(key: MigrationKey, descr: CurrentMigration) -> Self {
+    pub fn new(key: MigrationKey, descr: CurrentMigration) -> Self {
         FutureMigration {
             key,
             parent: descr.parent,
@@ -540,22 +552,7 @@ pub async fn unsafe_populate(
             return Ok(data);
         }
         if let Some(proposal) = &data.proposed {
-            let mut placeholders = BTreeMap::new();
-            if !proposal.required_user_input.is_empty() {
-                for input in &proposal.required_user_input {
-                    let Some(expr) = make_default_expression(input) else {
-                        log::debug!(
-                            "Cannot fill placeholder {} \
-                                    into {:?}, input info: {:?}",
-                            input.placeholder,
-                            proposal.statements,
-                            input
-                        );
-                        return Err(CantResolve)?;
-                    };
-                    placeholders.insert(input.placeholder.clone(), expr);
-                }
-            }
+            let placeholders = default_placeholders(proposal)?;
             if !apply_proposal(cli, proposal, &placeholders).await? {
                 execute(cli, "ALTER CURRENT MIGRATION REJECT PROPOSED", None).await?;
             }
@@ -566,7 +563,27 @@ pub async fn unsafe_populate(
     }
 }
 
-async fn apply_proposal(
+/// Fills in all the required user input of the proposal with the default
+/// expressions
+pub fn default_placeholders(proposal: &Proposal) -> anyhow::Result<BTreeMap<String, String>> {
+    let mut placeholders = BTreeMap::new();
+    for input in &proposal.required_user_input {
+        let Some(expr) = make_default_expression(input) else {
+            log::debug!(
+                "Cannot fill placeholder {} \
+                        into {:?}, input info: {:?}",
+                input.placeholder,
+                proposal.statements,
+                input
+            );
+            return Err(CantResolve)?;
+        };
+        placeholders.insert(input.placeholder.clone(), expr);
+    }
+    Ok(placeholders)
+}
+
+pub async fn apply_proposal(
     cli: &mut Connection,
     proposal: &Proposal,
     placeholders: &BTreeMap<String, String>,
@@ -900,6 +917,14 @@ impl InteractiveMigration<'_> {
     }
 }
 
+/// Asks the user about the remaining proposals of the current migration
+pub async fn interactive_populate(
+    cli: &mut Connection,
+    options: &Command,
+) -> anyhow::Result<CurrentMigration> {
+    InteractiveMigration::new(cli).run(options).await
+}
+
 async fn run_interactive(
     _ctx: &Context,
     cli: &mut Connection,
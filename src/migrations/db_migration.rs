@@ -1,3 +1,4 @@
+use gel_errors::InvalidReferenceError;
 use indexmap::IndexMap;
 use std::collections::{BTreeMap, BTreeSet};
 
@@ -123,6 +124,24 @@ pub(crate) async fn read_all(
     Ok(linearize_db_migrations(migrations))
 }
 
+/// Returns the time each migration was applied at, keyed by migration name
+pub(crate) async fn read_applied_at(
+    cli: &mut Connection,
+) -> anyhow::Result<BTreeMap<String, String>> {
+    let rows = cli
+        .query::<(String, String), _>(
+            "FOR m IN schema::Migration UNION (m.name, <str>m.created_at)",
+            &(),
+        )
+        .await;
+    match rows {
+        Ok(rows) => Ok(rows.into_iter().collect()),
+        // older servers don't record it
+        Err(e) if e.is::<InvalidReferenceError>() => Ok(BTreeMap::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
 pub(crate) async fn find_by_prefix(
     cli: &mut Connection,
     prefix: &str,
@@ -0,0 +1,155 @@
+use crate::async_try;
+use crate::branding::BRANDING_CLI_CMD;
+use crate::commands::{ExitCode, Options};
+use crate::connect::Connection;
+use crate::migrations::context::Context;
+use crate::migrations::create::{self, CantResolve, CurrentMigration, SAFE_CONFIDENCE};
+use crate::migrations::edb::{execute, execute_if_connected, query_row};
+use crate::migrations::migration;
+use crate::migrations::options::MigrationDiff;
+use crate::migrations::status::migrations_applied;
+use crate::migrations::timeout;
+use crate::print::{self, Highlight};
+
+#[derive(serde::Serialize, Debug)]
+struct Change {
+    text: String,
+    confidence: f64,
+    /// Low confidence or requires user input, i.e. `migration create
+    /// --non-interactive` would refuse to apply this without `--allow-unsafe`
+    #[serde(rename = "unsafe")]
+    is_unsafe: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    required_user_input: Vec<String>,
+}
+
+#[derive(serde::Serialize, Debug)]
+struct Diff {
+    parent: String,
+    changes: Vec<Change>,
+    /// False if the last change requires user input, so the changes after
+    /// it can't be computed
+    complete: bool,
+}
+
+pub async fn diff(
+    cli: &mut Connection,
+    cmd: &MigrationDiff,
+    opts: &Options,
+) -> Result<(), anyhow::Error> {
+    let ctx = Context::for_migration_config(&cmd.cfg, false, opts.skip_hooks, true).await?;
+    let migrations = migration::read_all(&ctx, true).await?;
+    let Some(parent) = migrations_applied(cli, &ctx, &migrations).await? else {
+        return Err(ExitCode::new(3).into());
+    };
+
+    let old_timeout = timeout::inhibit_for_transaction(cli).await?;
+    let (changes, complete) = async_try! {
+        async {
+            // Proposals are applied to get the next ones, so everything is
+            // done in a transaction that is never committed.
+            execute(cli, "START TRANSACTION", None).await?;
+            async_try! {
+                async {
+                    create::execute_start_migration(&ctx, cli).await?;
+                    collect_changes(cli).await
+                },
+                finally async {
+                    execute_if_connected(cli, "ROLLBACK").await
+                }
+            }
+        },
+        finally async {
+            timeout::restore_for_transaction(cli, old_timeout).await
+        }
+    }?;
+    let has_changes = !changes.is_empty();
+
+    if cmd.json {
+        let diff = Diff {
+            parent,
+            changes,
+            complete,
+        };
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else if has_changes {
+        eprintln!("Schema changes not covered by migrations (after {parent}):");
+        for change in &changes {
+            if change.is_unsafe {
+                println!(
+                    "{} (confidence {:.2})",
+                    "-- unsafe:".danger(),
+                    change.confidence
+                );
+                for input in &change.required_user_input {
+                    println!("{} {input}", "-- input required:".danger());
+                }
+            }
+            println!("{};", change.text.trim_end().trim_end_matches(';'));
+        }
+        if !complete {
+            println!(
+                "{}",
+                "-- further changes can't be computed without user input".danger()
+            );
+        }
+        print::error!("Schema files and migrations are out of sync.");
+        eprintln!("  Use `{BRANDING_CLI_CMD} migration create`.");
+    } else {
+        print::msg!(
+            "{} Last migration: {}.",
+            "Schema files and migrations are in sync."
+                .emphasized()
+                .success(),
+            parent.emphasized(),
+        );
+    }
+
+    if has_changes {
+        return Err(ExitCode::new(2).into());
+    }
+    Ok(())
+}
+
+/// Returns the changes and whether all of them could be computed
+async fn collect_changes(cli: &mut Connection) -> anyhow::Result<(Vec<Change>, bool)> {
+    let mut changes = Vec::new();
+    loop {
+        let data = query_row::<CurrentMigration>(cli, "DESCRIBE CURRENT MIGRATION AS JSON").await?;
+        if data.complete {
+            return Ok((changes, true));
+        }
+        let Some(proposal) = &data.proposed else {
+            return Err(CantResolve)?;
+        };
+        let is_unsafe =
+            proposal.confidence < SAFE_CONFIDENCE || !proposal.required_user_input.is_empty();
+        let to_changes = || {
+            proposal.statements.iter().map(|statement| Change {
+                text: statement.text.clone(),
+                confidence: proposal.confidence,
+                is_unsafe,
+                required_user_input: proposal
+                    .required_user_input
+                    .iter()
+                    .map(|input| input.prompt.clone())
+                    .collect(),
+            })
+        };
+        let placeholders = match create::default_placeholders(proposal) {
+            Ok(placeholders) => placeholders,
+            // the proposal can't be applied without user input, so it is
+            // reported as is and the following ones are unknown
+            Err(e) if e.is::<CantResolve>() => {
+                changes.extend(to_changes());
+                return Ok((changes, false));
+            }
+            Err(e) => return Err(e),
+        };
+        if !create::apply_proposal(cli, proposal, &placeholders).await? {
+            execute(cli, "ALTER CURRENT MIGRATION REJECT PROPOSED", None).await?;
+            continue;
+        }
+        changes.extend(to_changes());
+    }
+}
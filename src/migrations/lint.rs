@@ -0,0 +1,635 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use edgeql_parser::tokenizer::{Kind, Tokenizer};
+use fn_error_context::context;
+use regex::Regex;
+use serde_json::json;
+use tokio::fs;
+
+use crate::branding::BRANDING_CLI_CMD;
+use crate::commands::{ExitCode, Options};
+use crate::migrations::context::Context;
+use crate::migrations::grammar::parse_migration;
+use crate::migrations::migration;
+use crate::migrations::options::{LintFormat, MigrationLint};
+use crate::print::{self, AsRelativeToCurrentDir, Highlight};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Note,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Rule {
+    DropType,
+    DropPointer,
+    RequiredWithoutUsing,
+    CardinalityChange,
+    TypeChange,
+    IndexOnExistingType,
+    DataRewrite,
+}
+
+const RULES: &[Rule] = &[
+    Rule::DropType,
+    Rule::DropPointer,
+    Rule::RequiredWithoutUsing,
+    Rule::CardinalityChange,
+    Rule::TypeChange,
+    Rule::IndexOnExistingType,
+    Rule::DataRewrite,
+];
+
+impl Rule {
+    fn id(&self) -> &'static str {
+        match self {
+            Rule::DropType => "drop-type",
+            Rule::DropPointer => "drop-pointer",
+            Rule::RequiredWithoutUsing => "required-without-using",
+            Rule::CardinalityChange => "cardinality-change",
+            Rule::TypeChange => "type-change",
+            Rule::IndexOnExistingType => "index-on-existing-type",
+            Rule::DataRewrite => "data-rewrite",
+        }
+    }
+    fn description(&self) -> &'static str {
+        match self {
+            Rule::DropType => "Dropping an object type deletes all of its objects",
+            Rule::DropPointer => "Dropping a property or link deletes its data",
+            Rule::RequiredWithoutUsing => {
+                "Making a pointer required without a `USING` expression \
+                 fails if any object has no value"
+            }
+            Rule::CardinalityChange => "Changing the cardinality of a pointer rewrites its data",
+            Rule::TypeChange => "Changing the type of a pointer casts all of its data",
+            Rule::IndexOnExistingType => {
+                "Indexes on existing types are built inside of the migration \
+                 transaction, which blocks writes for a long time on large types"
+            }
+            Rule::DataRewrite => {
+                "Data is modified in the same transaction as the schema, \
+                 which locks the affected types until the migration is done"
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Finding {
+    rule: Rule,
+    severity: Severity,
+    message: String,
+    line: usize,
+    column: usize,
+}
+
+/// A DDL command along with the commands nested in its `{ }` block
+#[derive(Debug, Default)]
+struct Command {
+    words: Vec<String>,
+    offset: usize,
+    children: Vec<Command>,
+}
+
+impl Command {
+    fn word(&self, idx: usize) -> &str {
+        self.words.get(idx).map(|w| &w[..]).unwrap_or("")
+    }
+    fn starts_with(&self, prefix: &[&str]) -> bool {
+        self.words.len() >= prefix.len()
+            && self
+                .words
+                .iter()
+                .zip(prefix)
+                .all(|(w, p)| w.eq_ignore_ascii_case(p))
+    }
+    fn contains(&self, word: &str) -> bool {
+        self.words.iter().any(|w| w.eq_ignore_ascii_case(word))
+    }
+    /// Returns `(kind, name)` for commands like `ALTER TYPE default::User`
+    fn subject(&self) -> Option<(&'static str, String)> {
+        const QUALIFIERS: &[&str] = &[
+            "create",
+            "alter",
+            "drop",
+            "abstract",
+            "scalar",
+            "required",
+            "optional",
+            "single",
+            "multi",
+            "overloaded",
+        ];
+        let mut idx = 0;
+        while QUALIFIERS
+            .iter()
+            .any(|q| self.word(idx).eq_ignore_ascii_case(q))
+        {
+            idx += 1;
+        }
+        let kind = ["type", "property", "link"]
+            .into_iter()
+            .find(|k| self.word(idx).eq_ignore_ascii_case(k))?;
+        let mut name = self.word(idx + 1).to_string();
+        idx += 2;
+        while self.word(idx) == "::" {
+            name.push_str("::");
+            name.push_str(self.word(idx + 1));
+            idx += 2;
+        }
+        Some((kind, name))
+    }
+}
+
+enum Token {
+    Semicolon,
+    OpenBrace,
+    CloseBrace,
+    Other(String),
+}
+
+fn parse_commands(tokens: &mut impl Iterator<Item = (Token, usize)>) -> Vec<Command> {
+    let mut result = Vec::new();
+    let mut cmd = Command::default();
+    while let Some((token, offset)) = tokens.next() {
+        match token {
+            Token::Semicolon | Token::CloseBrace => {
+                if !cmd.words.is_empty() || !cmd.children.is_empty() {
+                    result.push(std::mem::take(&mut cmd));
+                }
+                if matches!(token, Token::CloseBrace) {
+                    return result;
+                }
+            }
+            Token::OpenBrace => {
+                let children = parse_commands(tokens);
+                cmd.children.extend(children);
+            }
+            Token::Other(text) => {
+                if cmd.words.is_empty() {
+                    cmd.offset = offset;
+                }
+                cmd.words.push(text);
+            }
+        }
+    }
+    if !cmd.words.is_empty() {
+        result.push(cmd);
+    }
+    result
+}
+
+fn check_command(cmd: &Command, ancestors: &[&Command], findings: &mut Vec<(Rule, String, usize)>) {
+    let mut report = |rule, message: String| findings.push((rule, message, cmd.offset));
+    let path = ancestors
+        .iter()
+        .chain([&cmd])
+        .filter_map(|c| c.subject())
+        .map(|(_, name)| name)
+        .collect::<Vec<_>>()
+        .join(".");
+    // anything nested in a top-level `ALTER` changes an existing object
+    // (type, scalar type, abstract link, ...)
+    let on_existing = ancestors.first().is_some_and(|c| c.starts_with(&["alter"]));
+
+    if ancestors.is_empty() {
+        if cmd.starts_with(&["drop", "type"]) {
+            report(
+                Rule::DropType,
+                format!("Dropping type {path} deletes all of its objects"),
+            );
+        }
+        let is_query = [
+            "insert", "update", "delete", "for", "with", "select", "group",
+        ]
+        .iter()
+        .any(|w| cmd.starts_with(&[*w]));
+        if is_query
+            && ["insert", "update", "delete"]
+                .iter()
+                .any(|w| cmd.contains(w))
+        {
+            report(
+                Rule::DataRewrite,
+                "Data modification inside a schema migration".into(),
+            );
+        }
+    } else if on_existing {
+        if let Some((kind, _)) = cmd.subject().filter(|_| cmd.starts_with(&["drop"])) {
+            report(
+                Rule::DropPointer,
+                format!("Dropping {kind} {path} deletes its data"),
+            );
+        }
+        let creates_required = cmd.starts_with(&["create", "required"])
+            && cmd.subject().is_some()
+            && !cmd.contains(":=")
+            && !cmd.children.iter().any(|c| {
+                c.starts_with(&["set", "default"])
+                    || (c.starts_with(&["set", "required"]) && c.contains("using"))
+            });
+        if (cmd.starts_with(&["set", "required"]) || creates_required) && !cmd.contains("using") {
+            report(
+                Rule::RequiredWithoutUsing,
+                format!("{path} is made required without a `USING` expression"),
+            );
+        }
+        if cmd.starts_with(&["set", "single"]) {
+            report(
+                Rule::CardinalityChange,
+                format!(
+                    "{path} is changed to single, this fails if any object has multiple values"
+                ),
+            );
+        } else if cmd.starts_with(&["set", "multi"]) {
+            report(
+                Rule::CardinalityChange,
+                format!("{path} is changed to multi, its data is moved to a separate table"),
+            );
+        }
+        if cmd.starts_with(&["set", "type"]) {
+            report(
+                Rule::TypeChange,
+                format!("Type of {path} is changed, existing data is cast to the new type"),
+            );
+        }
+        if cmd.starts_with(&["create"]) && cmd.contains("index") && !cmd.contains("deferred") {
+            report(
+                Rule::IndexOnExistingType,
+                format!("Index on existing type {path} is built inside the migration"),
+            );
+        }
+    }
+
+    let mut ancestors = ancestors.to_vec();
+    ancestors.push(cmd);
+    for child in &cmd.children {
+        check_command(child, &ancestors, findings);
+    }
+}
+
+fn severity(rule: Rule, message: &str) -> Severity {
+    match rule {
+        Rule::DropType | Rule::DropPointer | Rule::RequiredWithoutUsing => Severity::Error,
+        Rule::CardinalityChange if message.contains("to multi") => Severity::Note,
+        _ => Severity::Warning,
+    }
+}
+
+/// Returns rule ids allowed by `# lint: allow <rule>, ...` comments, by line
+fn allow_comments(text: &str) -> BTreeMap<usize, BTreeSet<String>> {
+    static ALLOW: std::sync::LazyLock<Regex> =
+        std::sync::LazyLock::new(|| Regex::new(r"#\s*lint:\s*allow\s+([a-z, -]+)").unwrap());
+    let mut result = BTreeMap::new();
+    for (idx, line) in text.lines().enumerate() {
+        if let Some(m) = ALLOW.captures(line) {
+            let rules = m[1]
+                .split(',')
+                .map(|r| r.trim().to_string())
+                .filter(|r| !r.is_empty())
+                .collect();
+            result.insert(idx + 1, rules);
+        }
+    }
+    result
+}
+
+/// Checks whether the finding is suppressed by a comment on the same line
+/// or on the comment lines right above it
+fn is_allowed(
+    text: &str,
+    allowed: &BTreeMap<usize, BTreeSet<String>>,
+    rule: Rule,
+    line: usize,
+) -> bool {
+    let lines = text.lines().collect::<Vec<_>>();
+    let allows = |line: usize| allowed.get(&line).is_some_and(|r| r.contains(rule.id()));
+    if allows(line) {
+        return true;
+    }
+    let mut line = line - 1;
+    while line > 0 && lines[line - 1].trim_start().starts_with('#') {
+        if allows(line) {
+            return true;
+        }
+        line -= 1;
+    }
+    false
+}
+
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = offset - before.rfind('\n').map(|p| p + 1).unwrap_or(0) + 1;
+    (line, column)
+}
+
+fn lint_text(text: &str) -> anyhow::Result<Vec<Finding>> {
+    let migration = parse_migration(text)?;
+    let (start, end) = migration.text_range;
+    let mut tokens = Vec::new();
+    for token in Tokenizer::new(&text[start..end]) {
+        let token = token.map_err(|e| anyhow::anyhow!("tokenizer error: {}", e.message))?;
+        let offset = start + token.span.start as usize;
+        let token = match token.kind {
+            Kind::Semicolon => Token::Semicolon,
+            Kind::OpenBrace => Token::OpenBrace,
+            Kind::CloseBrace => Token::CloseBrace,
+            _ => Token::Other(token.text.into_owned()),
+        };
+        tokens.push((token, offset));
+    }
+    let commands = parse_commands(&mut tokens.into_iter());
+    let allowed = allow_comments(text);
+
+    let mut findings = Vec::new();
+    for cmd in &commands {
+        let mut raw = Vec::new();
+        check_command(cmd, &[], &mut raw);
+        let (cmd_line, _) = position(text, cmd.offset);
+        for (rule, message, offset) in raw {
+            let (line, column) = position(text, offset);
+            if is_allowed(text, &allowed, rule, line) || is_allowed(text, &allowed, rule, cmd_line)
+            {
+                continue;
+            }
+            findings.push(Finding {
+                rule,
+                severity: severity(rule, &message),
+                message,
+                line,
+                column,
+            });
+        }
+    }
+    Ok(findings)
+}
+
+/// Lints statements that are not written to a migration file yet, returns
+/// severity, rule id and message of each finding
+pub fn lint_statements(
+    statements: &[String],
+) -> anyhow::Result<Vec<(Severity, &'static str, String)>> {
+    let text = format!(
+        "CREATE MIGRATION m1 ONTO initial {{\n{}\n}};\n",
+        statements.join("\n")
+    );
+    Ok(lint_text(&text)?
+        .into_iter()
+        .map(|f| (f.severity, f.rule.id(), f.message))
+        .collect())
+}
+
+#[context("could not lint {}", path.display())]
+async fn lint_file(path: &Path) -> anyhow::Result<Vec<Finding>> {
+    let text = fs::read_to_string(path).await?;
+    lint_text(&text)
+}
+
+fn print_json(findings: &[(PathBuf, Finding)]) -> anyhow::Result<()> {
+    let items = findings
+        .iter()
+        .map(|(path, f)| {
+            json!({
+                "file": path,
+                "line": f.line,
+                "column": f.column,
+                "rule": f.rule.id(),
+                "severity": f.severity,
+                "message": f.message,
+            })
+        })
+        .collect::<Vec<_>>();
+    println!("{}", serde_json::to_string_pretty(&items)?);
+    Ok(())
+}
+
+fn print_sarif(findings: &[(PathBuf, Finding)]) -> anyhow::Result<()> {
+    let rules = RULES
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id(),
+                "shortDescription": {"text": rule.description()},
+            })
+        })
+        .collect::<Vec<_>>();
+    let results = findings
+        .iter()
+        .map(|(path, f)| {
+            json!({
+                "ruleId": f.rule.id(),
+                "level": f.severity,
+                "message": {"text": f.message},
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": path.to_string_lossy().replace('\\', "/"),
+                        },
+                        "region": {"startLine": f.line, "startColumn": f.column},
+                    },
+                }],
+            })
+        })
+        .collect::<Vec<_>>();
+    let sarif = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {"driver": {"name": BRANDING_CLI_CMD, "rules": rules}},
+            "results": results,
+        }],
+    });
+    println!("{}", serde_json::to_string_pretty(&sarif)?);
+    Ok(())
+}
+
+pub async fn lint(cmd: &MigrationLint, opts: &Options) -> anyhow::Result<()> {
+    let paths = if cmd.files.is_empty() {
+        let ctx = Context::for_migration_config(&cmd.cfg, false, opts.skip_hooks, true).await?;
+        migration::read_all(&ctx, false)
+            .await?
+            .into_values()
+            .map(|m| m.path)
+            .collect()
+    } else {
+        cmd.files.clone()
+    };
+
+    let mut findings = Vec::new();
+    for path in paths {
+        for finding in lint_file(&path).await? {
+            findings.push((path.clone(), finding));
+        }
+    }
+
+    match cmd.format {
+        LintFormat::Json => print_json(&findings)?,
+        LintFormat::Sarif => print_sarif(&findings)?,
+        LintFormat::Text => {
+            for (path, f) in &findings {
+                let severity = match f.severity {
+                    Severity::Error => "error".danger(),
+                    Severity::Warning => "warning".warning(),
+                    Severity::Note => "note".muted(),
+                };
+                println!(
+                    "{}:{}:{}: {severity}[{}]: {}",
+                    path.as_relative().display(),
+                    f.line,
+                    f.column,
+                    f.rule.id(),
+                    f.message,
+                );
+            }
+            if findings.is_empty() {
+                print::success!("No issues found.");
+            } else {
+                eprintln!(
+                    "Suppress a finding with a `# lint: allow <rule>` comment \
+                     above the statement."
+                );
+            }
+        }
+    }
+
+    if findings.iter().any(|(_, f)| f.severity >= cmd.fail_on) {
+        return Err(ExitCode::new(1).into());
+    }
+    Ok(())
+}
+
+#[tokio::main(flavor = "current_thread")]
+pub async fn lint_no_conn(cmd: &MigrationLint, opts: &Options) -> anyhow::Result<()> {
+    lint(cmd, opts).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Rule, Severity, lint_text};
+
+    fn lint(body: &str) -> Vec<(Rule, Severity, usize)> {
+        let text = format!("CREATE MIGRATION m1abc ONTO initial {{\n{body}\n}};\n");
+        lint_text(&text)
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.rule, f.severity, f.line))
+            .collect()
+    }
+
+    #[test]
+    fn drops() {
+        assert_eq!(
+            lint(
+                "DROP TYPE default::User;
+                 ALTER TYPE default::Post {
+                     DROP PROPERTY title;
+                 };"
+            ),
+            vec![
+                (Rule::DropType, Severity::Error, 2),
+                (Rule::DropPointer, Severity::Error, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn new_types_are_fine() {
+        assert_eq!(
+            lint(
+                "CREATE TYPE default::User {
+                     CREATE REQUIRED PROPERTY name: std::str;
+                     CREATE INDEX ON (.name);
+                 };"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn alterations() {
+        assert_eq!(
+            lint(
+                "ALTER TYPE default::User {
+                     ALTER PROPERTY name {
+                         SET REQUIRED;
+                         SET SINGLE;
+                     };
+                     ALTER PROPERTY email {
+                         SET REQUIRED USING ('');
+                         SET TYPE std::str;
+                     };
+                     CREATE REQUIRED PROPERTY age: std::int64 {
+                         SET default := 0;
+                     };
+                     CREATE REQUIRED PROPERTY nickname: std::str {
+                         SET REQUIRED USING (.name);
+                     };
+                     CREATE INDEX ON (.email);
+                 };"
+            ),
+            vec![
+                (Rule::RequiredWithoutUsing, Severity::Error, 4),
+                (Rule::CardinalityChange, Severity::Warning, 5),
+                (Rule::TypeChange, Severity::Warning, 9),
+                (Rule::IndexOnExistingType, Severity::Warning, 17),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_existing_objects() {
+        assert_eq!(
+            lint(
+                "ALTER ABSTRACT LINK default::friends {
+                     DROP PROPERTY since;
+                 };
+                 ALTER SCALAR TYPE default::Status {
+                     SET TYPE std::int32;
+                 };
+                 CREATE ABSTRACT LINK default::follows {
+                     CREATE PROPERTY since: std::datetime;
+                 };"
+            ),
+            vec![
+                (Rule::DropPointer, Severity::Error, 3),
+                (Rule::TypeChange, Severity::Warning, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn data_rewrite() {
+        assert_eq!(
+            lint(
+                "SELECT 1;
+                 UPDATE default::User SET { name := 'x' };
+                 FOR x IN {1, 2} UNION (INSERT default::User { age := x });"
+            ),
+            vec![
+                (Rule::DataRewrite, Severity::Warning, 3),
+                (Rule::DataRewrite, Severity::Warning, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn allow_comments() {
+        assert_eq!(
+            lint(
+                "# lint: allow drop-type
+                 DROP TYPE default::User;
+                 ALTER TYPE default::Post {
+                     DROP PROPERTY title;  # lint: allow drop-pointer
+                     DROP LINK author;  # lint: allow drop-type
+                 };"
+            ),
+            vec![(Rule::DropPointer, Severity::Error, 6)]
+        );
+    }
+}
@@ -1,10 +1,97 @@
+use std::collections::BTreeMap;
+
+use indexmap::IndexMap;
+
 use crate::commands::Options;
 use crate::connect::Connection;
+use crate::migrations::NULL_MIGRATION;
 use crate::migrations::context::Context;
+use crate::migrations::db_migration::{DBMigration, MigrationGeneratedBy};
+use crate::migrations::migration::MigrationFile;
 use crate::migrations::options::MigrationLog;
 use crate::migrations::{db_migration, migration};
 use crate::print::Highlight;
 
+/// Migration as reported by `--json` output of `migration log` and
+/// `migration status`
+#[derive(serde::Serialize, Debug)]
+pub struct MigrationInfo {
+    id: String,
+    parent_id: Option<String>,
+    /// Path of the migration file, if the filesystem was checked
+    file: Option<String>,
+    /// Whether the migration is applied, if the database was checked
+    applied: Option<bool>,
+    applied_at: Option<String>,
+    /// One of `file`, `dev-mode` or `ddl-statement`
+    generated_by: &'static str,
+    /// One of `not-applied`, `missing-file` or `parent-mismatch`, if both
+    /// the filesystem and the database were checked
+    mismatch: Option<&'static str>,
+}
+
+fn parent_id(parent: Option<&String>) -> Option<String> {
+    parent.filter(|p| *p != NULL_MIGRATION).cloned()
+}
+
+fn generated_by(migration: &DBMigration) -> &'static str {
+    match migration.generated_by {
+        Some(MigrationGeneratedBy::DevMode) => "dev-mode",
+        Some(MigrationGeneratedBy::DDLStatement) => "ddl-statement",
+        None => "file",
+    }
+}
+
+/// Merges migrations from the filesystem and from the database, ordered as
+/// in the filesystem followed by ones that exist only in the database
+pub fn migration_infos(
+    fs: Option<&IndexMap<String, MigrationFile>>,
+    db: Option<(&IndexMap<String, DBMigration>, &BTreeMap<String, String>)>,
+) -> Vec<MigrationInfo> {
+    let mut result = Vec::new();
+    for (id, file) in fs.into_iter().flatten() {
+        let db_migration = db.and_then(|(db, _)| db.get(id));
+        let parent = parent_id(Some(&file.data.parent_id));
+        let mismatch = match (db, db_migration) {
+            (Some(_), None) => Some("not-applied"),
+            (Some(_), Some(m)) if parent_id(m.parent_names.first()) != parent => {
+                Some("parent-mismatch")
+            }
+            _ => None,
+        };
+        result.push(MigrationInfo {
+            id: id.clone(),
+            parent_id: parent,
+            file: Some(file.path.display().to_string()),
+            applied: db.map(|_| db_migration.is_some()),
+            applied_at: db.and_then(|(_, applied_at)| applied_at.get(id).cloned()),
+            generated_by: db_migration.map(generated_by).unwrap_or("file"),
+            mismatch,
+        });
+    }
+    if let Some((db, applied_at)) = db {
+        for (id, migration) in db {
+            if fs.is_some_and(|fs| fs.contains_key(id)) {
+                continue;
+            }
+            let mismatch = match migration.generated_by {
+                Some(MigrationGeneratedBy::DevMode) => None,
+                _ => fs.map(|_| "missing-file"),
+            };
+            result.push(MigrationInfo {
+                id: id.clone(),
+                parent_id: parent_id(migration.parent_names.first()),
+                file: None,
+                applied: Some(true),
+                applied_at: applied_at.get(id).cloned(),
+                generated_by: generated_by(migration),
+                mismatch,
+            });
+        }
+    }
+    result
+}
+
 pub async fn log(
     conn: &mut Connection,
     cmd: &MigrationLog,
@@ -25,6 +112,13 @@ pub async fn log_db(
     options: &MigrationLog,
 ) -> Result<(), anyhow::Error> {
     let migrations = db_migration::read_all(conn, false, false).await?;
+    if options.json {
+        let applied_at = db_migration::read_applied_at(conn).await?;
+        return print_json(
+            migration_infos(None, Some((&migrations, &applied_at))),
+            options,
+        );
+    }
     print(&migrations, options);
     Ok(())
 }
@@ -39,10 +133,22 @@ async fn log_fs_async(cmd: &MigrationLog, opts: &Options) -> Result<(), anyhow::
 
     let ctx = Context::for_migration_config(&cmd.cfg, false, opts.skip_hooks, true).await?;
     let migrations = migration::read_all(&ctx, true).await?;
+    if cmd.json {
+        return print_json(migration_infos(Some(&migrations), None), cmd);
+    }
     print(&migrations, cmd);
     Ok(())
 }
 
+fn print_json(mut infos: Vec<MigrationInfo>, options: &MigrationLog) -> anyhow::Result<()> {
+    if options.newest_first {
+        infos.reverse();
+    }
+    infos.truncate(options.limit.unwrap_or(infos.len()));
+    println!("{}", serde_json::to_string_pretty(&infos)?);
+    Ok(())
+}
+
 fn print<T>(migrations: &indexmap::IndexMap<String, T>, options: &MigrationLog) {
     let limit = options.limit.unwrap_or(migrations.len());
     if options.newest_first {
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use fs_err as fs;
 use indexmap::IndexMap;
 
@@ -8,11 +10,20 @@ use crate::migrations::migration::MigrationFile;
 use crate::migrations::{Context, apply, migration};
 
 pub struct MergeMigrations {
+    /// initial..base : the migrations shared by both branches
     pub base_migrations: IndexMap<String, MergeMigration>,
+    /// base..target : the migrations to merge into the current branch
     pub target_migrations: IndexMap<String, MergeMigration>,
+    /// base..source : the migrations that only exist on the current branch.
+    /// If there are any, a fast-forward merge is not possible.
+    pub source_migrations: IndexMap<String, DBMigration>,
 }
 
 impl MergeMigrations {
+    pub fn is_fast_forward(&self) -> bool {
+        self.source_migrations.is_empty()
+    }
+
     fn flatten(&self) -> IndexMap<&String, &MergeMigration> {
         let mut result = IndexMap::new();
 
@@ -60,41 +71,35 @@ pub async fn get_merge_migrations(
     base: &mut Connection,
     target: &mut Connection,
 ) -> anyhow::Result<MergeMigrations> {
-    let base_migrations = read_all(base, true, false).await?;
+    let mut base_migrations = read_all(base, true, false).await?;
     let mut target_migrations = read_all(target, true, false).await?;
 
+    let common = base_migrations
+        .keys()
+        .zip(target_migrations.keys())
+        .take_while(|(base_id, target_id)| base_id == target_id)
+        .count();
+
     // check if the base branch is up-to-date with target:
     // we do this by verifying that all target migrations
     // exist within the base migrations
-    if base_migrations.len() >= target_migrations.len()
-        && target_migrations.iter().enumerate().all(|(i, (k, _))| {
-            base_migrations
-                .get_index(i)
-                .map(|v| v.0 == k)
-                .unwrap_or(false)
-        })
-    {
+    if common == target_migrations.len() {
         anyhow::bail!("Already up to date.")
     }
 
-    let mut diverging_migrations = Vec::new();
-
-    for (index, (base_migration_id, _)) in base_migrations.iter().enumerate() {
-        if let Some((target_migration_id, _)) = target_migrations.get_index(index) {
-            if target_migration_id != base_migration_id {
-                diverging_migrations.push((index, base_migration_id, target_migration_id))
-            }
-        }
-    }
-
-    if !diverging_migrations.is_empty() {
+    if common < base_migrations.len() {
         eprintln!(
             "\nThe migration history of {} diverges from {}:",
             target.database(),
             base.database()
         );
 
-        for (index, expecting, actual) in &diverging_migrations {
+        for (index, (expecting, actual)) in base_migrations
+            .keys()
+            .zip(target_migrations.keys())
+            .enumerate()
+            .skip(common)
+        {
             eprintln!(
                 "{}. Expecting {} but has {}",
                 index,
@@ -104,27 +109,18 @@ pub async fn get_merge_migrations(
         }
 
         eprintln!();
-
-        anyhow::bail!(
-            "Cannot complete fast-forward merge, the histories of {0} and {1} are incompatible. Try rebasing {1} onto {0}",
-            base.database(),
-            target.database()
-        )
     }
 
+    let source_migrations = base_migrations.split_off(common);
     let mut target_merge_migrations: IndexMap<String, MergeMigration> = IndexMap::new();
     let mut base_merge_migrations: IndexMap<String, MergeMigration> = IndexMap::new();
 
-    for (index, (id, migration)) in target_migrations
-        .split_off(base_migrations.len())
-        .into_iter()
-        .enumerate()
-    {
+    for (index, (id, migration)) in target_migrations.split_off(common).into_iter().enumerate() {
         target_merge_migrations.insert(
             id,
             MergeMigration {
                 migration,
-                key: MigrationKey::Index((base_migrations.len() + index + 1) as u64),
+                key: MigrationKey::Index((common + index + 1) as u64),
             },
         );
     }
@@ -142,6 +138,7 @@ pub async fn get_merge_migrations(
     Ok(MergeMigrations {
         target_migrations: target_merge_migrations,
         base_migrations: base_merge_migrations,
+        source_migrations,
     })
 }
 
@@ -152,6 +149,13 @@ pub async fn write_merge_migrations(
     let temp_dir = tempfile::tempdir()?;
     let temp_ctx = Context::for_temp_path(temp_dir.path())?;
 
+    if !migrations.is_fast_forward() {
+        // migrations of the current branch are replaced by the combined one
+        for old in migration::read_names(context).await? {
+            fs::remove_file(old)?;
+        }
+    }
+
     for (_, migration) in migrations.flatten() {
         write_migration(&temp_ctx, migration, false).await?;
     }
@@ -182,3 +186,47 @@ pub async fn apply_merge_migration_files(
 
     apply::apply_migrations(connection, &migrations, context, true).await
 }
+
+/// Applies the migration created on top of the merged history, i.e. the
+/// one that is neither in the common nor in the target migrations
+pub async fn apply_combined_migration_file(
+    merge_migrations: &MergeMigrations,
+    context: &Context,
+    connection: &mut Connection,
+) -> anyhow::Result<()> {
+    let migrations: IndexMap<String, MigrationFile> = migration::read_all(context, true)
+        .await?
+        .into_iter()
+        .filter(|(id, _)| {
+            !merge_migrations.base_migrations.contains_key(id)
+                && !merge_migrations.target_migrations.contains_key(id)
+        })
+        .collect();
+
+    apply::apply_migrations(connection, &migrations, context, true).await
+}
+
+/// Copies all migration files aside, including the ones that are not
+/// applied to any branch yet, so they can be restored if the merge fails
+pub async fn backup_migration_files(context: &Context) -> anyhow::Result<tempfile::TempDir> {
+    let backup = tempfile::tempdir()?;
+    let dir = backup.path().join("migrations");
+    fs::create_dir_all(&dir)?;
+    for path in migration::read_names(context).await? {
+        fs::copy(&path, dir.join(path.file_name().unwrap()))?;
+    }
+    Ok(backup)
+}
+
+/// Brings back the migration files of the current branch after a failed
+/// three-way merge
+pub async fn restore_migration_files(context: &Context, backup: &Path) -> anyhow::Result<()> {
+    for old in migration::read_names(context).await? {
+        fs::remove_file(old)?;
+    }
+    let dir = context.schema_dir.join("migrations");
+    for path in migration::read_names(&Context::for_temp_path(backup)?).await? {
+        fs::copy(&path, dir.join(path.file_name().unwrap()))?;
+    }
+    Ok(())
+}
@@ -10,6 +10,7 @@ use regex::Regex;
 use tokio::fs;
 use tokio::io;
 
+use crate::branding::BRANDING_CLI_CMD;
 use crate::migrations::NULL_MIGRATION;
 use crate::migrations::context::Context;
 use crate::migrations::grammar::parse_migration;
@@ -149,6 +150,13 @@ async fn _read_names(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
     Ok(result)
 }
 
+pub async fn read_fixup_names(ctx: &Context) -> anyhow::Result<Vec<PathBuf>> {
+    let dir = ctx.schema_dir.join("fixups");
+    _read_names(dir.as_ref())
+        .await
+        .with_context(|| format!("could not list fixups in {}", dir.display()))
+}
+
 pub fn file_num(path: &Path) -> Option<u64> {
     path.file_stem().and_then(|x| x.to_str()).and_then(|x| {
         if x.contains('-') {
@@ -242,6 +250,28 @@ fn sort_revisions(
     Ok(res)
 }
 
+/// Returns the index of the only revision starting with `prefix`
+pub fn find_revision(
+    migrations: &IndexMap<String, MigrationFile>,
+    prefix: &str,
+) -> anyhow::Result<usize> {
+    let mut found = migrations
+        .keys()
+        .enumerate()
+        .filter(|(_, rev)| rev.starts_with(prefix));
+    match (found.next(), found.next()) {
+        (Some((idx, _)), None) => Ok(idx),
+        (Some(_), Some(_)) => {
+            anyhow::bail!("More than one revision matches prefix {:?}", prefix)
+        }
+        (None, _) => anyhow::bail!(
+            "No revision with prefix {:?} found in the filesystem. \
+             See `{BRANDING_CLI_CMD} migration log --from-fs`.",
+            prefix
+        ),
+    }
+}
+
 pub async fn read_all(
     ctx: &Context,
     validate_hashes: bool,
@@ -1,18 +1,23 @@
 pub mod context;
 
-mod db_migration;
+pub mod db_migration;
+mod diff;
 mod edb;
 mod edit;
 mod extract;
 mod grammar;
+mod lint;
 mod log;
 mod migration;
 mod print_error;
 mod prompt;
+mod revert;
 mod source_map;
 mod squash;
 mod status;
+mod temp_branch;
 mod timeout;
+mod verify;
 
 pub mod apply;
 pub mod create;
@@ -27,8 +32,12 @@ const NULL_MIGRATION: &str = "initial";
 
 pub use self::log::{log, log_fs};
 pub use context::Context;
+pub use diff::diff;
 pub use edit::{edit, edit_no_check};
 pub use extract::extract;
+pub use lint::{lint, lint_no_conn};
+pub use revert::revert;
 pub use status::status;
 pub use upgrade_check::upgrade_check;
 pub use upgrade_format::upgrade_format;
+pub use verify::{verify, verify_no_conn};
@@ -32,6 +32,34 @@ pub enum MigrationCmd {
     Status(ShowStatus),
     /// Show all migration versions.
     Log(MigrationLog),
+    /// Show schema changes that are not covered by migrations yet.
+    ///
+    /// Computes the DDL that `migration create` would generate, without
+    /// writing any files or changing the database. Exits with a non-zero
+    /// status if there are changes, so it can be used as a CI check that
+    /// schema files and migrations are in sync.
+    Diff(MigrationDiff),
+    /// Check migration files for destructive and risky operations.
+    ///
+    /// Works on the files only, no database connection is needed. A finding
+    /// can be suppressed by a `# lint: allow <rule>` comment on the line of
+    /// the offending statement or right above it (comments do not change
+    /// migration ids).
+    Lint(MigrationLint),
+    /// Check that migration files form a consistent history.
+    ///
+    /// Works on the files only, no database connection is needed. Checks
+    /// that migration ids match their contents, that the parent revisions
+    /// form a single chain without gaps and that file names are consistent,
+    /// e.g. to catch merge conflicts in the migrations directory in CI.
+    Verify(MigrationVerify),
+    /// Revert the schema to an earlier revision.
+    ///
+    /// Computes the DDL that takes the database back to the schema at the
+    /// specified revision, shows its implications for the existing data,
+    /// and then writes and applies it as a new migration. The history is
+    /// not rewritten, so the revert can be deployed as any other migration.
+    Revert(MigrationRevert),
     /// Edit migration file.
     ///
     /// Invokes $EDITOR on the last migration file, and then fixes
@@ -69,6 +97,84 @@ pub struct ShowStatus {
     /// Do not print any messages, only indicate success by exit status.
     #[arg(long)]
     pub quiet: bool,
+
+    /// Print the status and every migration in JSON format (the exit
+    /// status is the same as without this option).
+    #[arg(long, conflicts_with = "quiet")]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct MigrationDiff {
+    #[command(flatten)]
+    pub cfg: MigrationConfig,
+
+    /// Output the proposed statements in JSON format.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct MigrationLint {
+    #[command(flatten)]
+    pub cfg: MigrationConfig,
+
+    /// Migration files to check (default: all migrations in the schema
+    /// directory).
+    #[arg(value_hint=ValueHint::FilePath)]
+    pub files: Vec<PathBuf>,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value = "text")]
+    pub format: LintFormat,
+
+    /// Exit with a non-zero status if there are findings of this severity
+    /// or higher.
+    #[arg(long, value_enum, default_value = "error")]
+    pub fail_on: migrations::lint::Severity,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LintFormat {
+    Text,
+    Json,
+    Sarif,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct MigrationVerify {
+    #[command(flatten)]
+    pub cfg: MigrationConfig,
+
+    /// Output the issues found in JSON format.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(clap::Args, Clone, Debug)]
+pub struct MigrationRevert {
+    #[command(flatten)]
+    pub cfg: MigrationConfig,
+
+    /// The revision to revert the schema to.
+    ///
+    /// A unique revision prefix can be specified instead of a full
+    /// revision name.
+    #[arg(long)]
+    pub to_revision: String,
+
+    /// Only show the DDL and its implications, do not create a migration.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Do not ask for confirmation.
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Proceed even if the current branch is protected in the project
+    /// manifest.
+    #[arg(long)]
+    pub force_protected: bool,
 }
 
 #[derive(clap::Args, Clone, Debug)]
@@ -94,6 +200,11 @@ pub struct MigrationLog {
     /// Show maximum N revisions (default: no limit).
     #[arg(long)]
     pub limit: Option<usize>,
+
+    /// Print parent, file, and applied state of every revision in JSON
+    /// format.
+    #[arg(long)]
+    pub json: bool,
 }
 
 #[derive(clap::Args, Clone, Debug)]
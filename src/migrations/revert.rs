@@ -0,0 +1,187 @@
+use crate::async_try;
+use crate::commands::{ExitCode, Options};
+use crate::connect::Connection;
+use crate::migrations::apply;
+use crate::migrations::context::Context;
+use crate::migrations::create::{
+    self, CantResolve, CurrentMigration, FutureMigration, MigrationKey,
+};
+use crate::migrations::edb::{execute, execute_if_connected, query_row};
+use crate::migrations::lint::{self, Severity};
+use crate::migrations::migration;
+use crate::migrations::options::MigrationRevert;
+use crate::migrations::status::migrations_applied;
+use crate::migrations::temp_branch::{schema_at, with_temp_branch};
+use crate::migrations::timeout;
+use crate::portable::exit_codes;
+use crate::print::{self, Highlight};
+use crate::question;
+
+pub async fn revert(
+    cli: &mut Connection,
+    cmd: &MigrationRevert,
+    opts: &Options,
+) -> anyhow::Result<()> {
+    let ctx = Context::for_migration_config(&cmd.cfg, false, opts.skip_hooks, true).await?;
+    if let Some(project) = &ctx.project {
+        let branch = cli.get_current_branch().await?.to_string();
+        project.check_protected_branch(&branch, cmd.force_protected)?;
+    }
+
+    let migrations = migration::read_all(&ctx, true).await?;
+    let target_idx = migration::find_revision(&migrations, &cmd.to_revision)?;
+    let (target, _) = migrations.get_index(target_idx).expect("valid index");
+    let target = target.clone();
+    let Some(current) = migrations_applied(cli, &ctx, &migrations).await? else {
+        return Err(ExitCode::new(3).into());
+    };
+    if target == current {
+        print::msg!(
+            "{} Revision {}",
+            "Database is already at the target schema."
+                .emphasized()
+                .success(),
+            current.emphasized(),
+        );
+        return Ok(());
+    }
+
+    eprintln!("Building schema of revision {}...", target.emphasized());
+    let target_sdl = with_temp_branch(cli, opts, async |temp| {
+        schema_at(temp, &migrations, target_idx + 1).await
+    })
+    .await?;
+
+    let old_timeout = timeout::inhibit_for_transaction(cli).await?;
+    let descr = async_try! {
+        async {
+            execute(cli, format!("START MIGRATION TO {{ {target_sdl} }}"), None).await?;
+            async_try! {
+                async {
+                    let descr = populate(cli, cmd).await?;
+                    if descr.parent != current {
+                        anyhow::bail!("Database changed while computing the revert migration");
+                    }
+                    Ok(descr)
+                },
+                finally async {
+                    execute_if_connected(cli, "ABORT MIGRATION").await
+                }
+            }
+        },
+        finally async {
+            timeout::restore_for_transaction(cli, old_timeout).await
+        }
+    }?;
+
+    if descr.confirmed.is_empty() {
+        print::msg!(
+            "{} Schema of revision {} is the same as the current one.",
+            "Nothing to revert.".emphasized().success(),
+            target.emphasized(),
+        );
+        return Ok(());
+    }
+
+    eprintln!("The following DDL statements revert the schema to {target}:");
+    for statement in &descr.confirmed {
+        for line in statement.lines() {
+            println!("    {line}");
+        }
+    }
+    let findings = lint::lint_statements(&descr.confirmed)?;
+    if !findings.is_empty() {
+        eprintln!("Implications for the existing data:");
+        for (severity, rule, message) in &findings {
+            let prefix = match severity {
+                Severity::Error => "data loss".danger(),
+                Severity::Warning => "warning".warning(),
+                Severity::Note => "note".muted(),
+            };
+            eprintln!("  {prefix}[{rule}]: {message}");
+        }
+    }
+    if cmd.dry_run {
+        return Ok(());
+    }
+
+    if !cmd.non_interactive {
+        let q = question::Confirm::new_dangerous(
+            "Create and apply the migration reverting the schema?",
+        );
+        if !cli.ping_while(q.async_ask()).await? {
+            print::error!("Canceled.");
+            return Err(ExitCode::new(exit_codes::NOT_CONFIRMED).into());
+        }
+    }
+
+    let key = MigrationKey::Index((migrations.len() + 1) as u64);
+    create::write_migration(&ctx, &FutureMigration::new(key, descr), true).await?;
+    apply::run(
+        &apply::Command {
+            conn: None,
+            cfg: cmd.cfg.clone(),
+            quiet: false,
+            to_revision: None,
+            dev_mode: false,
+            force_protected: false,
+            no_index_build: false,
+            single_transaction: false,
+        },
+        cli,
+        opts,
+        false,
+    )
+    .await
+}
+
+/// Applies the proposals filling in required input with default values
+///
+/// If a proposal needs input that can't be filled in automatically, shows
+/// it and continues with interactive prompts (or fails when
+/// non-interactive).
+async fn populate(cli: &mut Connection, cmd: &MigrationRevert) -> anyhow::Result<CurrentMigration> {
+    loop {
+        let data = query_row::<CurrentMigration>(cli, "DESCRIBE CURRENT MIGRATION AS JSON").await?;
+        if data.complete {
+            return Ok(data);
+        }
+        let Some(proposal) = &data.proposed else {
+            return Err(CantResolve)?;
+        };
+        let Ok(placeholders) = create::default_placeholders(proposal) else {
+            eprintln!("The following statement needs input to revert the schema:");
+            for statement in &proposal.statements {
+                for line in statement.text.lines() {
+                    eprintln!("    {line}");
+                }
+            }
+            for input in &proposal.required_user_input {
+                eprintln!("  {} {}", "input required:".warning(), input.prompt);
+            }
+            if cmd.non_interactive {
+                anyhow::bail!(
+                    "Cannot revert the schema without user input, \
+                     run without `--non-interactive`"
+                );
+            }
+            let options = create::Command {
+                cfg: cmd.cfg.clone(),
+                squash: false,
+                from: None,
+                to: None,
+                non_interactive: false,
+                expert: false,
+                allow_unsafe: false,
+                allow_empty: false,
+                debug_print_queries: false,
+                debug_print_err: false,
+                quiet: false,
+            };
+            return create::interactive_populate(cli, &options).await;
+        };
+        if !create::apply_proposal(cli, proposal, &placeholders).await? {
+            execute(cli, "ALTER CURRENT MIGRATION REJECT PROPOSED", None).await?;
+        }
+    }
+}
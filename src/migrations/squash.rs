@@ -3,6 +3,7 @@ use std::io;
 use std::path::{Path, PathBuf};
 
 use anyhow::Context as _;
+use indexmap::IndexMap;
 use tokio::fs;
 
 use crate::async_try;
@@ -10,14 +11,17 @@ use crate::branding::BRANDING_CLI_CMD;
 use crate::bug;
 use crate::commands::{ExitCode, Options};
 use crate::connect::Connection;
+use crate::migrations::apply::apply_migration;
 use crate::migrations::context::Context;
 use crate::migrations::create;
 use crate::migrations::create::{CurrentMigration, FutureMigration, MigrationKey, MigrationToText};
 use crate::migrations::create::{execute_start_migration, write_migration};
 use crate::migrations::create::{first_migration, normal_migration};
 use crate::migrations::edb::{execute, execute_if_connected};
-use crate::migrations::migration;
+use crate::migrations::grammar::parse_migration;
+use crate::migrations::migration::{self, Migration, MigrationFile};
 use crate::migrations::status::migrations_applied;
+use crate::migrations::temp_branch::{schema_at, with_temp_branch};
 use crate::migrations::timeout;
 use crate::print::{Highlight, msg};
 use crate::question::Confirm;
@@ -30,6 +34,9 @@ pub async fn run(
     let ctx = Context::for_migration_config(&cmd.cfg, cmd.non_interactive, opts.skip_hooks, false)
         .await?;
     let migrations = migration::read_all(&ctx, true).await?;
+    if cmd.from.is_some() || cmd.to.is_some() {
+        return run_partial(cmd, conn, opts, &ctx, &migrations).await;
+    }
     let Some(db_rev) = migrations_applied(conn, &ctx, &migrations).await? else {
         return Err(ExitCode::new(3).into());
     };
@@ -75,6 +82,212 @@ pub async fn run(
     Ok(())
 }
 
+/// Squashes the migrations in the range `(from, to]`, keeping the earlier
+/// ones and rewriting the later ones on top of the squashed revision.
+///
+/// A fixup is written for every replaced revision, so databases at any of
+/// them can be migrated to the new history.
+async fn run_partial(
+    cmd: &create::Command,
+    conn: &mut Connection,
+    opts: &Options,
+    ctx: &Context,
+    migrations: &IndexMap<String, MigrationFile>,
+) -> anyhow::Result<()> {
+    // index of the first squashed migration
+    let first = match &cmd.from {
+        Some(prefix) => migration::find_revision(migrations, prefix)? + 1,
+        None => 0,
+    };
+    // index of the last squashed migration
+    let last = match &cmd.to {
+        Some(prefix) => migration::find_revision(migrations, prefix)?,
+        None => migrations.len().saturating_sub(1),
+    };
+    if last < first + 1 {
+        msg!("Less than two revisions in the range. No actions will be taken.");
+        return Ok(());
+    }
+    if !cmd.non_interactive {
+        conn.ping_while(confirm_partial_squashing(migrations, first, last))
+            .await?;
+    }
+
+    let (squashed, mut fixups) = with_temp_branch(conn, opts, async |temp| {
+        squash_range(temp, ctx, migrations, first, last).await
+    })
+    .await?;
+
+    let squashed_id = squashed.id()?.to_owned();
+    fixups.push(FutureMigration::empty(
+        MigrationKey::Fixup {
+            target_revision: squashed_id.clone(),
+        },
+        &migrations[last].data.id,
+    ));
+
+    // Later migrations must be rewritten as their ids depend on the parent
+    let mut rewritten = Vec::new();
+    let mut parent = squashed_id.clone();
+    for (idx, old) in migrations.values().enumerate().skip(last + 1) {
+        let text = fs::read_to_string(&old.path)
+            .await
+            .with_context(|| format!("cannot read {:?}", old.path))?;
+        let (text, id) = rebase_migration(&text, &old.data, &parent)?;
+        let path = renumbered_path(ctx, idx, first, last, &id);
+        fixups.push(FutureMigration::empty(
+            MigrationKey::Fixup {
+                target_revision: id.clone(),
+            },
+            &old.data.id,
+        ));
+        rewritten.push((path, text));
+        parent = id;
+    }
+
+    let mut drop = TwoStageRemove::new(ctx);
+    for old in migrations.values().skip(first) {
+        drop.rename(&old.path).await?;
+    }
+    for fixup in &fixups {
+        write_migration(ctx, fixup, false).await?;
+    }
+    write_migration(ctx, &squashed, false).await?;
+    for (path, text) in rewritten {
+        fs::write(&path, text)
+            .await
+            .with_context(|| format!("cannot write {path:?}"))?;
+    }
+    drop.commit().await?;
+
+    msg!(
+        "Squashed {} revisions into {}.",
+        last + 1 - first,
+        squashed_id.emphasized()
+    );
+    print_final_message(true)?;
+    Ok(())
+}
+
+/// Replaces the parent of a migration, returns the new text and id
+fn rebase_migration(
+    text: &str,
+    data: &Migration,
+    parent: &str,
+) -> anyhow::Result<(String, String)> {
+    let text = data.replace_parent_id(text, parent);
+    let data = parse_migration(&text)?;
+    let id = data.expected_id(&text)?;
+    let text = data.replace_id(&text, &id);
+    Ok((text, id))
+}
+
+/// Path of the migration at index `idx` after migrations at `first..=last`
+/// are squashed into one
+fn renumbered_path(ctx: &Context, idx: usize, first: usize, last: usize, id: &str) -> PathBuf {
+    let num = idx - (last - first) + 1;
+    ctx.schema_dir
+        .join("migrations")
+        .join(format!("{:05}-{}.edgeql", num, &id[..7]))
+}
+
+/// Returns the squashed migration and fixups for every revision in the
+/// range, except the last one (the fixup for it is always empty)
+async fn squash_range(
+    cli: &mut Connection,
+    ctx: &Context,
+    migrations: &IndexMap<String, MigrationFile>,
+    first: usize,
+    last: usize,
+) -> anyhow::Result<(FutureMigration, Vec<FutureMigration>)> {
+    let target_sdl = schema_at(cli, migrations, last + 1).await?;
+    let files = migrations.values().collect::<Vec<_>>();
+
+    let old_timeout = timeout::inhibit_for_transaction(cli).await?;
+    async_try! {
+        async {
+            execute(cli, "START MIGRATION REWRITE", None).await?;
+            async_try! {
+                async {
+                    for migration in &files[..first] {
+                        apply_migration(cli, migration, false).await?;
+                    }
+                    let descr = migration_to(cli, ctx, &target_sdl).await?;
+                    let squashed = FutureMigration::new(
+                        MigrationKey::Index(first as u64 + 1),
+                        descr,
+                    );
+                    let squashed_id = squashed.id()?.to_owned();
+                    let mut fixups = Vec::new();
+                    for migration in &files[first..last] {
+                        apply_migration(cli, migration, false).await?;
+                        let descr = migration_to(cli, ctx, &target_sdl).await?;
+                        let key = MigrationKey::Fixup {
+                            target_revision: squashed_id.clone(),
+                        };
+                        fixups.push(FutureMigration::new(key, descr));
+                    }
+                    Ok((squashed, fixups))
+                },
+                finally async {
+                    execute_if_connected(cli, "ABORT MIGRATION REWRITE").await
+                }
+            }
+        },
+        finally async {
+            timeout::restore_for_transaction(cli, old_timeout).await
+        }
+    }
+}
+
+/// Populates a migration from the current schema to `sdl`
+async fn migration_to(
+    cli: &mut Connection,
+    ctx: &Context,
+    sdl: &str,
+) -> anyhow::Result<CurrentMigration> {
+    execute(cli, format!("START MIGRATION TO {{ {sdl} }}"), None).await?;
+    async_try! {
+        async {
+            create::unsafe_populate(ctx, cli).await
+        },
+        finally async {
+            execute_if_connected(cli, "ABORT MIGRATION").await
+        }
+    }
+}
+
+async fn confirm_partial_squashing(
+    migrations: &IndexMap<String, MigrationFile>,
+    first: usize,
+    last: usize,
+) -> anyhow::Result<()> {
+    let (first_rev, _) = migrations.get_index(first).expect("valid index");
+    let (last_rev, _) = migrations.get_index(last).expect("valid index");
+    msg!(
+        "Revisions {} to {} ({} total) will be squashed into one.",
+        first_rev.emphasized(),
+        last_rev.emphasized(),
+        last + 1 - first
+    );
+    if last + 1 < migrations.len() {
+        msg!(
+            "{} later revision(s) will be rewritten on top of it.",
+            migrations.len() - last - 1
+        );
+    }
+    msg!("Data migrations in the range are discarded.");
+    msg!(
+        "Fixups are created so that databases at any of the replaced \
+           revisions can be migrated to the new history."
+    );
+    msg!();
+    if !Confirm::new("Proceed?").async_ask().await? {
+        return Err(ExitCode::new(0))?;
+    }
+    Ok(())
+}
+
 struct TwoStageRemove<'a> {
     ctx: &'a Context,
     filenames: Vec<PathBuf>,
@@ -331,3 +544,48 @@ async fn test_two_stage_remove() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{rebase_migration, renumbered_path};
+    use crate::migrations::context::Context;
+    use crate::migrations::grammar::parse_migration;
+
+    #[test]
+    fn rebase_later_migration() {
+        let text = "CREATE MIGRATION m1aaaaa\n    ONTO m1old\n{\n  CREATE TYPE default::Foo;\n};\n";
+        let data = parse_migration(text).unwrap();
+        let (new_text, id) = rebase_migration(text, &data, "m1new").unwrap();
+        let rebased = parse_migration(&new_text).unwrap();
+        assert_eq!(rebased.parent_id, "m1new");
+        assert_eq!(rebased.id, id);
+        assert_eq!(rebased.expected_id(&new_text).unwrap(), id);
+        assert!(new_text.contains("CREATE TYPE default::Foo;"));
+
+        // ids depend on the parent, so every later migration gets a new one
+        let (_, other) = rebase_migration(text, &data, "m1other").unwrap();
+        assert_ne!(id, other);
+    }
+
+    #[test]
+    fn renumbering() {
+        let ctx = Context::for_temp_path("schema").unwrap();
+        let id = "m1abcdefgh";
+        // 00002..00004 are squashed into 00002, so 00005 becomes 00003
+        assert_eq!(
+            renumbered_path(&ctx, 4, 1, 3, id),
+            Path::new("schema/migrations/00003-m1abcde.edgeql"),
+        );
+        assert_eq!(
+            renumbered_path(&ctx, 5, 1, 3, id),
+            Path::new("schema/migrations/00004-m1abcde.edgeql"),
+        );
+        // squashed from the first migration
+        assert_eq!(
+            renumbered_path(&ctx, 2, 0, 1, id),
+            Path::new("schema/migrations/00002-m1abcde.edgeql"),
+        );
+    }
+}
@@ -6,7 +6,9 @@ use crate::commands::{ExitCode, Options};
 use crate::connect::Connection;
 use crate::migrations::context::Context;
 use crate::migrations::create::{CurrentMigration, execute_start_migration};
+use crate::migrations::db_migration;
 use crate::migrations::edb::execute_if_connected;
+use crate::migrations::log::{MigrationInfo, migration_infos};
 use crate::migrations::migration::{self, MigrationFile};
 use crate::migrations::options::ShowStatus;
 use crate::print::{self, Highlight};
@@ -47,6 +49,9 @@ pub async fn status(
     cmd: &ShowStatus,
     opts: &Options,
 ) -> Result<(), anyhow::Error> {
+    if cmd.json {
+        return status_json(cli, cmd, opts).await;
+    }
     let ctx = Context::for_migration_config(&cmd.cfg, cmd.quiet, opts.skip_hooks, true).await?;
     let migrations = migration::read_all(&ctx, true).await?;
     match up_to_date_check(cli, &ctx, &migrations).await? {
@@ -63,6 +68,51 @@ pub async fn status(
     }
 }
 
+#[derive(serde::Serialize)]
+struct StatusInfo {
+    /// One of `up-to-date`, `out-of-date` (migrations are not applied or
+    /// the database has unknown ones) or `schema-differs` (schema files
+    /// have changes not covered by migrations)
+    status: &'static str,
+    database_revision: Option<String>,
+    filesystem_revision: Option<String>,
+    migrations: Vec<MigrationInfo>,
+}
+
+async fn status_json(
+    cli: &mut Connection,
+    cmd: &ShowStatus,
+    opts: &Options,
+) -> Result<(), anyhow::Error> {
+    let ctx = Context::for_migration_config(&cmd.cfg, true, opts.skip_hooks, true).await?;
+    let migrations = migration::read_all(&ctx, true).await?;
+    let db_migrations = db_migration::read_all(cli, false, true).await?;
+    let applied_at = db_migration::read_applied_at(cli).await?;
+    let code = match up_to_date_check(cli, &ctx, &migrations).await {
+        Ok(Some(_)) => 0,
+        Ok(None) => 3,
+        Err(e) => match e.downcast_ref::<ExitCode>() {
+            Some(code) => code.code(),
+            None => return Err(e),
+        },
+    };
+    let info = StatusInfo {
+        status: match code {
+            0 => "up-to-date",
+            3 => "out-of-date",
+            _ => "schema-differs",
+        },
+        database_revision: db_migrations.keys().last().cloned(),
+        filesystem_revision: migrations.keys().last().cloned(),
+        migrations: migration_infos(Some(&migrations), Some((&db_migrations, &applied_at))),
+    };
+    println!("{}", serde_json::to_string_pretty(&info)?);
+    if code != 0 {
+        return Err(ExitCode::new(code).into());
+    }
+    Ok(())
+}
+
 pub async fn migrations_applied(
     cli: &mut Connection,
     ctx: &Context,
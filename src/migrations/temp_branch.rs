@@ -0,0 +1,62 @@
+use edgeql_parser::helpers::quote_name;
+use indexmap::IndexMap;
+use uuid::Uuid;
+
+use crate::commands::Options;
+use crate::connect::Connection;
+use crate::migrations::apply;
+use crate::migrations::edb::query_row;
+use crate::migrations::migration::MigrationFile;
+use crate::print;
+
+/// Runs `f` connected to a new empty branch, which is dropped afterwards
+pub async fn with_temp_branch<T>(
+    cli: &mut Connection,
+    opts: &Options,
+    f: impl AsyncFnOnce(&mut Connection) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let temp_branch = Uuid::new_v4().to_string();
+    let (status, _warnings) = cli
+        .execute(
+            &format!("create empty branch {}", quote_name(&temp_branch)),
+            &(),
+        )
+        .await?;
+    print::completion(status);
+
+    let result = async {
+        let mut connector = opts.conn_params.clone();
+        let mut temp_connection = connector.branch(&temp_branch)?.connect().await?;
+        f(&mut temp_connection).await
+    }
+    .await;
+
+    match cli
+        .execute(
+            &format!("drop branch {} force", quote_name(&temp_branch)),
+            &(),
+        )
+        .await
+    {
+        Ok((status, _warnings)) => print::completion(status),
+        // don't hide the original error
+        Err(e) if result.is_err() => {
+            log::error!("Cannot drop temporary branch {temp_branch:?}: {e:#}");
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    result
+}
+
+/// Applies the first `end` migrations and returns the resulting schema
+pub async fn schema_at(
+    cli: &mut Connection,
+    migrations: &IndexMap<String, MigrationFile>,
+    end: usize,
+) -> anyhow::Result<String> {
+    if let Some(migrations) = migrations.get_range(..end) {
+        apply::apply_migrations_inner(cli, migrations, false).await?;
+    }
+    Ok(query_row::<String>(cli, "DESCRIBE SCHEMA AS SDL").await?)
+}
@@ -0,0 +1,539 @@
+use std::collections::btree_map::{self, BTreeMap};
+use std::collections::hash_map::{self, HashMap};
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use regex::Regex;
+use serde_json::json;
+use tokio::fs;
+
+use crate::commands::{ExitCode, Options};
+use crate::migrations::NULL_MIGRATION;
+use crate::migrations::context::Context;
+use crate::migrations::grammar::parse_migration;
+use crate::migrations::migration::{self, Migration, MigrationFile, file_num};
+use crate::migrations::options::MigrationVerify;
+use crate::print::{self, AsRelativeToCurrentDir, Highlight};
+
+#[derive(Debug)]
+struct Issue {
+    path: PathBuf,
+    message: String,
+}
+
+impl Issue {
+    fn new(path: &Path, message: impl Into<String>) -> Issue {
+        Issue {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Reads and parses the file, reporting parse errors and id mismatches as
+/// issues rather than failing on the first one
+async fn read_file(path: &Path, issues: &mut Vec<Issue>) -> anyhow::Result<Option<MigrationFile>> {
+    let text = fs::read_to_string(path)
+        .await
+        .with_context(|| format!("could not read migration file {}", path.display()))?;
+    let data = match parse_migration(&text) {
+        Ok(data) => data,
+        Err(e) => {
+            issues.push(Issue::new(path, format!("cannot parse migration: {e:#}")));
+            return Ok(None);
+        }
+    };
+    check_id(path, &text, &data, issues);
+    Ok(Some(MigrationFile {
+        path: path.to_path_buf(),
+        fixup_target: None,
+        data,
+    }))
+}
+
+fn check_id(path: &Path, text: &str, data: &Migration, issues: &mut Vec<Issue>) {
+    if !data.id.starts_with("m1") {
+        issues.push(Issue::new(
+            path,
+            format!("cannot parse migration name {:?}", data.id),
+        ));
+        return;
+    }
+    match data.expected_id(text) {
+        Ok(id) if id != data.id => issues.push(Issue::new(
+            path,
+            format!(
+                "declared id {} does not match the id computed from \
+                 the migration contents: {id}",
+                data.id
+            ),
+        )),
+        Ok(_) => {}
+        Err(e) => issues.push(Issue::new(
+            path,
+            format!("cannot compute migration id: {e:#}"),
+        )),
+    }
+}
+
+/// Checks that files are named `NNNNN-<id prefix>.edgeql` (or the legacy
+/// `NNNNN.edgeql`) and are numbered from 1 without gaps and duplicates.
+///
+/// Returns `false` if numbers are not sequential, so that the position in the
+/// revision history is not worth checking.
+fn check_names(migrations: &[MigrationFile], issues: &mut Vec<Issue>) -> bool {
+    let name_re = Regex::new(r"^\d{5}(?:-([a-z0-9]{7}))?$").unwrap();
+    let mut numbers = BTreeMap::new();
+    let mut sequential = true;
+    for item in migrations {
+        let stem = item.path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let id_prefix = item.data.id.get(..7).unwrap_or(&item.data.id);
+        match name_re.captures(stem) {
+            Some(captures) => {
+                if let Some(suffix) = captures.get(1) {
+                    if suffix.as_str() != id_prefix {
+                        issues.push(Issue::new(
+                            &item.path,
+                            format!(
+                                "file name does not match revision {}, \
+                                 should be `{}-{id_prefix}.edgeql`",
+                                item.data.id,
+                                &stem[..5],
+                            ),
+                        ));
+                    }
+                }
+            }
+            None => issues.push(Issue::new(
+                &item.path,
+                format!("file name should be `NNNNN-{id_prefix}.edgeql`"),
+            )),
+        }
+        let Some(num) = file_num(&item.path) else {
+            sequential = false;
+            continue;
+        };
+        match numbers.entry(num) {
+            btree_map::Entry::Vacant(v) => {
+                v.insert(&item.path);
+            }
+            btree_map::Entry::Occupied(o) => {
+                sequential = false;
+                issues.push(Issue::new(
+                    &item.path,
+                    format!(
+                        "has the same number as `{}`",
+                        o.get().as_relative().display()
+                    ),
+                ));
+            }
+        }
+    }
+    let mut expected = 1;
+    for (&num, path) in &numbers {
+        if num != expected {
+            sequential = false;
+            issues.push(Issue::new(
+                path,
+                if num == expected + 1 {
+                    format!("migration number {expected:05} is missing")
+                } else {
+                    format!(
+                        "migration numbers {expected:05} to {:05} are missing",
+                        num - 1
+                    )
+                },
+            ));
+        }
+        expected = num + 1;
+    }
+    sequential
+}
+
+/// Checks that parent revisions form a single chain starting from the
+/// initial one, returns the last revision of the chain
+fn check_chain(
+    migrations: &[MigrationFile],
+    check_numbers: bool,
+    issues: &mut Vec<Issue>,
+) -> Option<String> {
+    let mut by_parent = HashMap::new();
+    let mut reported = HashSet::new();
+    for item in migrations {
+        match by_parent.entry(item.data.parent_id.as_str()) {
+            hash_map::Entry::Vacant(v) => {
+                v.insert(item);
+            }
+            hash_map::Entry::Occupied(o) => {
+                reported.insert(&item.path);
+                issues.push(Issue::new(
+                    &item.path,
+                    format!(
+                        "has the same parent revision {:?} as `{}`. \
+                         Multiple branches in revision history are not \
+                         supported, please rebase one of the branches on \
+                         top of the other.",
+                        item.data.parent_id,
+                        o.get().path.as_relative().display(),
+                    ),
+                ));
+            }
+        }
+    }
+
+    let mut in_chain = HashSet::new();
+    let mut parent_id = NULL_MIGRATION;
+    let mut counter = 1;
+    while let Some(item) = by_parent.remove(parent_id) {
+        if check_numbers && file_num(&item.path) != Some(counter) {
+            issues.push(Issue::new(
+                &item.path,
+                format!("is migration number {counter} in the revision history"),
+            ));
+        }
+        in_chain.insert(&item.path);
+        parent_id = &item.data.id;
+        counter += 1;
+    }
+
+    let ids = migrations
+        .iter()
+        .map(|m| m.data.id.as_str())
+        .collect::<BTreeSet<_>>();
+    for item in migrations {
+        if in_chain.contains(&item.path) || reported.contains(&item.path) {
+            continue;
+        }
+        let parent = &item.data.parent_id;
+        if parent != NULL_MIGRATION && !ids.contains(parent.as_str()) {
+            issues.push(Issue::new(
+                &item.path,
+                format!("parent revision {parent:?} does not exist"),
+            ));
+        } else {
+            issues.push(Issue::new(
+                &item.path,
+                format!(
+                    "revision {} is not reachable from the initial migration",
+                    item.data.id
+                ),
+            ));
+        }
+    }
+    (counter > 1).then(|| parent_id.to_string())
+}
+
+/// Checks that fixups are named `<parent>-<target>.edgeql`, the parent
+/// matches the one in the file and the target is in `migrations`
+fn check_fixups(fixups: &[MigrationFile], migrations: &[MigrationFile], issues: &mut Vec<Issue>) {
+    let revisions = migrations
+        .iter()
+        .map(|m| m.data.id.as_str())
+        .collect::<HashSet<_>>();
+    for item in fixups {
+        let stem = item.path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        let Some((parent, target)) = stem.split_once('-') else {
+            issues.push(Issue::new(
+                &item.path,
+                format!(
+                    "file name should be `{}-<target revision>.edgeql`",
+                    item.data.parent_id
+                ),
+            ));
+            continue;
+        };
+        if parent != item.data.parent_id {
+            issues.push(Issue::new(
+                &item.path,
+                format!(
+                    "file name does not match parent revision {:?}, \
+                     should be `{}-{target}.edgeql`",
+                    item.data.parent_id, item.data.parent_id,
+                ),
+            ));
+        }
+        if target.is_empty() || target.contains('-') {
+            issues.push(Issue::new(
+                &item.path,
+                "file name should be `<parent revision>-<target revision>.edgeql`",
+            ));
+        } else if !revisions.contains(target) {
+            // the parent is usually a squashed revision, but the target
+            // must be in the history
+            issues.push(Issue::new(
+                &item.path,
+                format!("target revision {target:?} does not exist in the migration history"),
+            ));
+        }
+    }
+}
+
+pub async fn verify(cmd: &MigrationVerify, opts: &Options) -> anyhow::Result<()> {
+    let ctx = Context::for_migration_config(&cmd.cfg, false, opts.skip_hooks, true).await?;
+    let mut issues = Vec::new();
+
+    let mut migrations = Vec::new();
+    for path in migration::read_names(&ctx).await? {
+        if let Some(item) = read_file(&path, &mut issues).await? {
+            migrations.push(item);
+        }
+    }
+    migrations.sort_by(|a, b| a.path.cmp(&b.path));
+    let mut fixups = Vec::new();
+    for path in migration::read_fixup_names(&ctx).await? {
+        if let Some(item) = read_file(&path, &mut issues).await? {
+            fixups.push(item);
+        }
+    }
+    fixups.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let sequential = check_names(&migrations, &mut issues);
+    let revision = check_chain(&migrations, sequential, &mut issues);
+    check_fixups(&fixups, &migrations, &mut issues);
+
+    if cmd.json {
+        let issues = issues
+            .iter()
+            .map(|issue| json!({"file": issue.path, "message": issue.message}))
+            .collect::<Vec<_>>();
+        let result = json!({
+            "revision": revision,
+            "migrations": migrations.len(),
+            "fixups": fixups.len(),
+            "issues": issues,
+        });
+        println!("{}", serde_json::to_string_pretty(&result)?);
+    } else if issues.is_empty() {
+        print::success!(
+            "Migration history is consistent ({} migrations, {} fixups).",
+            migrations.len(),
+            fixups.len(),
+        );
+        if let Some(revision) = &revision {
+            eprintln!("Last revision: {}", revision.emphasized());
+        }
+    } else {
+        for issue in &issues {
+            println!(
+                "{}: {}: {}",
+                issue.path.as_relative().display(),
+                "error".danger(),
+                issue.message,
+            );
+        }
+        print::error!("Found {} issue(s) in migration history.", issues.len());
+    }
+
+    if !issues.is_empty() {
+        return Err(ExitCode::new(1).into());
+    }
+    Ok(())
+}
+
+#[tokio::main(flavor = "current_thread")]
+pub async fn verify_no_conn(cmd: &MigrationVerify, opts: &Options) -> anyhow::Result<()> {
+    verify(cmd, opts).await
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_chain, check_fixups, check_names};
+    use crate::migrations::NULL_MIGRATION;
+    use crate::migrations::migration::{Migration, MigrationFile};
+
+    fn mk(input: &[(&str, &str, &str)]) -> Vec<MigrationFile> {
+        input
+            .iter()
+            .map(|&(id, parent, filename)| MigrationFile {
+                path: filename.into(),
+                fixup_target: None,
+                data: Migration {
+                    id: id.into(),
+                    id_range: (0, 0),
+                    parent_id: parent.into(),
+                    parent_id_range: (0, 0),
+                    message: None,
+                    text_range: (0, 0),
+                },
+            })
+            .collect()
+    }
+
+    fn verify(input: &[(&str, &str, &str)]) -> (Option<String>, Vec<(String, String)>) {
+        let migrations = mk(input);
+        let mut issues = Vec::new();
+        let sequential = check_names(&migrations, &mut issues);
+        let revision = check_chain(&migrations, sequential, &mut issues);
+        let issues = issues
+            .into_iter()
+            .map(|i| (i.path.display().to_string(), i.message))
+            .collect();
+        (revision, issues)
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(verify(&[]), (None, vec![]));
+    }
+
+    #[test]
+    fn consistent() {
+        let (revision, issues) = verify(&[
+            ("m1aaaaaa1", NULL_MIGRATION, "00001-m1aaaaa.edgeql"),
+            ("m1bbbbbb2", "m1aaaaaa1", "00002-m1bbbbb.edgeql"),
+            ("m1cccccc3", "m1bbbbbb2", "00003.edgeql"),
+        ]);
+        assert_eq!(issues, vec![]);
+        assert_eq!(revision.as_deref(), Some("m1cccccc3"));
+    }
+
+    #[test]
+    fn bad_name() {
+        let (_, issues) = verify(&[
+            ("m1aaaaaa1", NULL_MIGRATION, "00001-m1bbbbb.edgeql"),
+            ("m1bbbbbb2", "m1aaaaaa1", "2.edgeql"),
+        ]);
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "00001-m1bbbbb.edgeql".into(),
+                    "file name does not match revision m1aaaaaa1, \
+                     should be `00001-m1aaaaa.edgeql`"
+                        .into()
+                ),
+                (
+                    "2.edgeql".into(),
+                    "file name should be `NNNNN-m1bbbbb.edgeql`".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn gap() {
+        let (revision, issues) = verify(&[
+            ("m1aaaaaa1", NULL_MIGRATION, "00001-m1aaaaa.edgeql"),
+            ("m1bbbbbb2", "m1aaaaaa1", "00003-m1bbbbb.edgeql"),
+        ]);
+        assert_eq!(
+            issues,
+            vec![(
+                "00003-m1bbbbb.edgeql".into(),
+                "migration number 00002 is missing".into()
+            )]
+        );
+        assert_eq!(revision.as_deref(), Some("m1bbbbbb2"));
+    }
+
+    #[test]
+    fn merge_conflict() {
+        let (revision, issues) = verify(&[
+            ("m1aaaaaa1", NULL_MIGRATION, "00001-m1aaaaa.edgeql"),
+            ("m1bbbbbb2", "m1aaaaaa1", "00002-m1bbbbb.edgeql"),
+            ("m1cccccc3", "m1aaaaaa1", "00002-m1ccccc.edgeql"),
+        ]);
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "00002-m1ccccc.edgeql".into(),
+                    "has the same number as `00002-m1bbbbb.edgeql`".into()
+                ),
+                (
+                    "00002-m1ccccc.edgeql".into(),
+                    "has the same parent revision \"m1aaaaaa1\" as \
+                     `00002-m1bbbbb.edgeql`. Multiple branches in revision \
+                     history are not supported, please rebase one of the \
+                     branches on top of the other."
+                        .into()
+                ),
+            ]
+        );
+        assert_eq!(revision.as_deref(), Some("m1bbbbbb2"));
+    }
+
+    #[test]
+    fn broken_chain() {
+        let (revision, issues) = verify(&[
+            ("m1aaaaaa1", NULL_MIGRATION, "00001-m1aaaaa.edgeql"),
+            ("m1bbbbbb2", "m1xxxxxx0", "00002-m1bbbbb.edgeql"),
+            ("m1cccccc3", "m1bbbbbb2", "00003-m1ccccc.edgeql"),
+        ]);
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "00002-m1bbbbb.edgeql".into(),
+                    "parent revision \"m1xxxxxx0\" does not exist".into()
+                ),
+                (
+                    "00003-m1ccccc.edgeql".into(),
+                    "revision m1cccccc3 is not reachable from \
+                     the initial migration"
+                        .into()
+                ),
+            ]
+        );
+        assert_eq!(revision.as_deref(), Some("m1aaaaaa1"));
+    }
+
+    #[test]
+    fn wrong_order() {
+        let (_, issues) = verify(&[
+            ("m1aaaaaa1", NULL_MIGRATION, "00002-m1aaaaa.edgeql"),
+            ("m1bbbbbb2", "m1aaaaaa1", "00001-m1bbbbb.edgeql"),
+        ]);
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "00002-m1aaaaa.edgeql".into(),
+                    "is migration number 1 in the revision history".into()
+                ),
+                (
+                    "00001-m1bbbbb.edgeql".into(),
+                    "is migration number 2 in the revision history".into()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn fixups() {
+        let fixups = mk(&[
+            ("m1ffffff1", "m1aaaaaa1", "m1aaaaaa1-m1bbbbbb2.edgeql"),
+            ("m1ffffff2", "m1aaaaaa1", "m1cccccc3-m1bbbbbb2.edgeql"),
+            ("m1ffffff3", "m1aaaaaa1", "m1aaaaaa1.edgeql"),
+            ("m1ffffff4", "m1aaaaaa1", "m1aaaaaa1-m1dddddd4.edgeql"),
+        ]);
+        let migrations = mk(&[("m1bbbbbb2", "initial", "00001-m1bbbbb.edgeql")]);
+        let mut issues = Vec::new();
+        check_fixups(&fixups, &migrations, &mut issues);
+        let issues = issues
+            .into_iter()
+            .map(|i| (i.path.display().to_string(), i.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            vec![
+                (
+                    "m1cccccc3-m1bbbbbb2.edgeql".to_string(),
+                    "file name does not match parent revision \"m1aaaaaa1\", \
+                     should be `m1aaaaaa1-m1bbbbbb2.edgeql`"
+                        .to_string()
+                ),
+                (
+                    "m1aaaaaa1.edgeql".to_string(),
+                    "file name should be `m1aaaaaa1-<target revision>.edgeql`".to_string()
+                ),
+                (
+                    "m1aaaaaa1-m1dddddd4.edgeql".to_string(),
+                    "target revision \"m1dddddd4\" does not exist in the migration history"
+                        .to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -21,10 +21,11 @@ use crate::commands::ExitCode;
 use crate::connect::Connection;
 use crate::options::Options;
 use crate::options::Query;
-use crate::outputs::tab_separated;
+use crate::outputs::{csv, tab_separated};
 use crate::print::{self, PrintError};
 use crate::repl;
 use crate::statement::{EndOfFile, read_statement};
+use crate::variables::{ArgValues, args_value};
 
 #[tokio::main(flavor = "current_thread")]
 pub async fn noninteractive_main(q: &Query, options: &Options) -> Result<(), anyhow::Error> {
@@ -56,12 +57,30 @@ pub async fn noninteractive_main(q: &Query, options: &Options) -> Result<(), any
         }
     };
 
+    let mut args = ArgValues::new(&q.args, q.args_file.as_deref())?;
+
     if let Some(filename) = &q.file {
         if filename == "-" {
-            Box::pin(interpret_file(&mut stdin(), options, fmt, lang)).await?;
+            Box::pin(interpret_file(
+                &mut stdin(),
+                options,
+                fmt,
+                lang,
+                q.csv_flatten,
+                &mut args,
+            ))
+            .await?;
         } else {
             let mut file = AsyncFile::open(filename).await?;
-            Box::pin(interpret_file(&mut file, options, fmt, lang)).await?;
+            Box::pin(interpret_file(
+                &mut file,
+                options,
+                fmt,
+                lang,
+                q.csv_flatten,
+                &mut args,
+            ))
+            .await?;
         }
     } else if let Some(queries) = &q.queries {
         let mut conn = Box::pin(options.create_connector().await?.connect()).await?;
@@ -72,7 +91,16 @@ pub async fn noninteractive_main(q: &Query, options: &Options) -> Result<(), any
                                Use the dedicated `{BRANDING_CLI_CMD} analyze` command."
                 );
             }
-            run_query(&mut conn, query, options, fmt, lang).await?;
+            run_query(
+                &mut conn,
+                query,
+                options,
+                fmt,
+                lang,
+                q.csv_flatten,
+                &mut args,
+            )
+            .await?;
         }
     } else {
         print::error!(
@@ -81,6 +109,11 @@ pub async fn noninteractive_main(q: &Query, options: &Options) -> Result<(), any
         );
     }
 
+    let unused = args.unused();
+    if !unused.is_empty() {
+        print::warn!("Arguments not used by any query: {}", unused.join(", "));
+    }
+
     Ok(())
 }
 
@@ -90,7 +123,16 @@ pub async fn interpret_stdin(
     fmt: repl::OutputFormat,
     lang: repl::InputLanguage,
 ) -> Result<(), anyhow::Error> {
-    return Box::pin(interpret_file(&mut stdin(), options, fmt, lang)).await;
+    let mut args = ArgValues::default();
+    return Box::pin(interpret_file(
+        &mut stdin(),
+        options,
+        fmt,
+        lang,
+        repl::CsvFlatten::Json,
+        &mut args,
+    ))
+    .await;
 }
 
 async fn interpret_file<T>(
@@ -98,6 +140,8 @@ async fn interpret_file<T>(
     options: &Options,
     fmt: repl::OutputFormat,
     lang: repl::InputLanguage,
+    flatten: repl::CsvFlatten,
+    args: &mut ArgValues,
 ) -> Result<(), anyhow::Error>
 where
     T: AsyncRead + Unpin,
@@ -120,7 +164,7 @@ where
                            Use the dedicated `{BRANDING_CLI_CMD} analyze` command."
             );
         }
-        run_query(&mut conn, stmt, options, fmt, lang).await?;
+        run_query(&mut conn, stmt, options, fmt, lang, flatten, args).await?;
     }
     Ok(())
 }
@@ -131,8 +175,10 @@ async fn run_query(
     options: &Options,
     fmt: repl::OutputFormat,
     lang: repl::InputLanguage,
+    flatten: repl::CsvFlatten,
+    args: &mut ArgValues,
 ) -> Result<(), anyhow::Error> {
-    _run_query(conn, stmt, options, fmt, lang)
+    _run_query(conn, stmt, options, fmt, lang, flatten, args)
         .await
         .map_err(|err| {
             if let Some(err) = err.downcast_ref::<gel_errors::Error>() {
@@ -152,6 +198,8 @@ async fn _run_query(
     _options: &Options,
     fmt: repl::OutputFormat,
     lang: repl::InputLanguage,
+    flatten: repl::CsvFlatten,
+    args: &mut ArgValues,
 ) -> Result<(), anyhow::Error> {
     use crate::repl::OutputFormat::*;
 
@@ -166,6 +214,7 @@ async fn _run_query(
         expected_cardinality: Cardinality::Many,
     };
     let data_description = conn.parse(&flags, stmt).await?;
+    let input = args_value(&data_description.input()?, args, lang)?;
 
     let mut cfg = print::Config::new();
     if let Some((Width(w), _h)) = terminal_size() {
@@ -174,7 +223,7 @@ async fn _run_query(
     cfg.colors(stdout().is_terminal());
 
     let mut items = conn
-        .execute_stream(&flags, stmt, &data_description, &())
+        .execute_stream(&flags, stmt, &data_description, &input)
         .await?;
 
     print::query_warnings(items.warnings(), stmt)?;
@@ -261,6 +310,22 @@ async fn _run_query(
                 stdout().lock().write_all(text.as_bytes())?;
             }
         }
+        repl::OutputFormat::Csv => {
+            let mut header = true;
+            while let Some(row) = items.next().await.transpose()? {
+                let mut text = String::new();
+                if std::mem::take(&mut header) {
+                    if let Some(names) = csv::format_header(&row) {
+                        text += &names;
+                        text += "\r\n";
+                    }
+                }
+                text += &csv::format_row(&row, flatten)?;
+                // trying to make writes atomic if possible
+                text += "\r\n";
+                stdout().lock().write_all(text.as_bytes())?;
+            }
+        }
         repl::OutputFormat::Json => {
             while let Some(row) = items.next().await.transpose()? {
                 let text = match row {
@@ -29,7 +29,7 @@ use crate::portable;
 use crate::portable::local::runstate_dir;
 use crate::print::{self, AsRelativeToCurrentDir, Highlight, err_marker};
 use crate::project;
-use crate::repl::{InputLanguage, OutputFormat};
+use crate::repl::{CsvFlatten, InputLanguage, OutputFormat};
 use crate::tty_password;
 
 const MAX_TERM_WIDTH: usize = 100;
@@ -525,8 +525,8 @@ pub struct Query {
     #[command(flatten)]
     pub conn: ConnectionOptions,
 
-    /// Output format: `json`, `json-pretty`, `json-lines`, `tab-separated`.
-    /// Default is `json-pretty`.
+    /// Output format: `json`, `json-pretty`, `json-lines`, `tab-separated`,
+    /// `csv`. Default is `json-pretty`.
     // todo: can't use `arg(default='json-pretty')` just yet, as we
     // need to see if the user did actually specify some output
     // format or not. We need that to support the now deprecated
@@ -539,11 +539,27 @@ pub struct Query {
     #[arg(short = 'L', long)]
     pub input_language: Option<InputLanguage>,
 
+    /// How nested data is written with `--output-format=csv`: `json`
+    /// (default) or `join`
+    #[arg(long, value_name = "MODE", default_value = "json")]
+    pub csv_flatten: CsvFlatten,
+
     /// Filename to execute queries from.
     /// Pass `--file -` to execute queries from stdin.
     #[arg(short = 'f', long)]
     pub file: Option<String>,
 
+    /// Value of a query parameter as `NAME=VALUE`, can be repeated.
+    /// The value is parsed according to the parameter type, like in
+    /// the interactive prompt.
+    #[arg(long = "arg", value_name = "NAME=VALUE", value_parser = parse_query_arg)]
+    pub args: Vec<(String, String)>,
+
+    /// Read query parameters from a JSON object in a file.
+    /// Values given with `--arg` take precedence.
+    #[arg(long, value_name = "FILE")]
+    pub args_file: Option<PathBuf>,
+
     pub queries: Option<Vec<String>>,
 }
 
@@ -629,6 +645,17 @@ fn parse_duration(value: &str) -> anyhow::Result<Duration> {
     }
 }
 
+fn parse_query_arg(value: &str) -> anyhow::Result<(String, String)> {
+    let Some((name, value)) = value.split_once('=') else {
+        anyhow::bail!("expected NAME=VALUE");
+    };
+    let name = name.trim().trim_start_matches('$');
+    if name.is_empty() {
+        anyhow::bail!("argument name is empty");
+    }
+    Ok((name.to_string(), value.to_string()))
+}
+
 fn say_option_is_deprecated(option_name: &str, suggestion: &str) {
     let error = "warning:".to_string().emphasized().warning();
     let instead = suggestion.to_string().success();
@@ -959,6 +986,9 @@ impl Options {
                 output_format,
                 input_language: Some(InputLanguage::EdgeQl),
                 file: None,
+                csv_flatten: CsvFlatten::Json,
+                args: Vec::new(),
+                args_file: None,
                 conn: args.conn.clone(),
             }))
         } else {
@@ -0,0 +1,276 @@
+use gel_protocol::value::Value;
+
+use crate::repl::CsvFlatten;
+
+/// Column names for the result rows (`None` for scalar results)
+pub fn format_header(row: &Value) -> Option<String> {
+    use Value::*;
+
+    let names = match row {
+        Object { shape, .. } => shape
+            .elements
+            .iter()
+            .filter(|el| !el.flag_implicit)
+            .map(|el| {
+                if el.flag_link_property {
+                    format!("@{}", el.name)
+                } else {
+                    el.name.clone()
+                }
+            })
+            .collect::<Vec<_>>(),
+        NamedTuple { shape, .. } => shape.elements.iter().map(|el| el.name.clone()).collect(),
+        SQLRow { shape, .. } => shape.elements.iter().map(|el| el.name.clone()).collect(),
+        Tuple(items) => (0..items.len()).map(|i| i.to_string()).collect(),
+        _ => return None,
+    };
+    Some(join_record(names.iter().map(|n| &n[..])))
+}
+
+/// Formats a single record, without line terminator
+pub fn format_row(row: &Value, flatten: CsvFlatten) -> Result<String, anyhow::Error> {
+    use Value::*;
+
+    let cells = match row {
+        Object { shape, fields } => shape
+            .elements
+            .iter()
+            .zip(fields)
+            .filter(|(el, _)| !el.flag_implicit)
+            .map(|(_, v)| format_cell(v.as_ref(), flatten))
+            .collect::<Result<Vec<_>, _>>()?,
+        NamedTuple { fields, .. } => fields
+            .iter()
+            .map(|v| format_cell(Some(v), flatten))
+            .collect::<Result<Vec<_>, _>>()?,
+        SQLRow { fields, .. } => fields
+            .iter()
+            .map(|v| format_cell(v.as_ref(), flatten))
+            .collect::<Result<Vec<_>, _>>()?,
+        Tuple(items) => items
+            .iter()
+            .map(|v| format_cell(Some(v), flatten))
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => vec![format_cell(Some(row), flatten)?],
+    };
+    Ok(join_record(cells.iter().map(|c| &c[..])))
+}
+
+fn join_record<'a>(cells: impl Iterator<Item = &'a str>) -> String {
+    cells.map(quote).collect::<Vec<_>>().join(",")
+}
+
+/// Quotes a field as described in RFC 4180
+fn quote(cell: &str) -> String {
+    if cell.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+fn format_cell(v: Option<&Value>, flatten: CsvFlatten) -> Result<String, anyhow::Error> {
+    use Value::*;
+
+    let Some(v) = v else {
+        return Ok(String::new());
+    };
+    if let Some(text) = scalar_to_string(v) {
+        return Ok(text);
+    }
+    if flatten == CsvFlatten::Join {
+        match v {
+            Set(items) | Array(items) => {
+                return Ok(items
+                    .iter()
+                    .map(|item| format_cell(Some(item), flatten))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join("; "));
+            }
+            Object { shape, fields } => {
+                let mut visible = shape
+                    .elements
+                    .iter()
+                    .zip(fields)
+                    .filter(|(el, _)| !el.flag_implicit);
+                // links with a single property, like `friends: {name}`, are
+                // written as the property values
+                if let (Some((_, value)), None) = (visible.next(), visible.next()) {
+                    return format_cell(value.as_ref(), flatten);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(to_json(v)?.to_string())
+}
+
+fn scalar_to_string(v: &Value) -> Option<String> {
+    use Value::*;
+
+    let text = match v {
+        Nothing => String::new(),
+        Uuid(uuid) => uuid.to_string(),
+        Str(s) => s.clone(),
+        Bytes(b) => format!("\\x{}", hex::encode(b)),
+        Int16(v) => v.to_string(),
+        Int32(v) => v.to_string(),
+        Int64(v) => v.to_string(),
+        Float32(v) => v.to_string(),
+        Float64(v) => v.to_string(),
+        BigInt(v) => num_bigint::BigInt::from(v).to_string(),
+        Decimal(v) => bigdecimal::BigDecimal::from(v).to_string(),
+        Bool(v) => v.to_string(),
+        Json(v) => v.to_string(),
+        Enum(v) => v.to_string(),
+        Datetime(v) => format!("{v:?}"),
+        LocalDatetime(v) => format!("{v:?}"),
+        LocalDate(v) => format!("{v:?}"),
+        LocalTime(v) => format!("{v:?}"),
+        Duration(v) => v.to_string(),
+        RelativeDuration(v) => v.to_string(),
+        DateDuration(v) => v.to_string(),
+        ConfigMemory(v) => v.to_string(),
+        _ => return None,
+    };
+    Some(text)
+}
+
+/// Nested data is written as JSON, similar to the server's JSON output
+fn to_json(v: &Value) -> Result<serde_json::Value, anyhow::Error> {
+    use Value::*;
+    use serde_json::Value as J;
+
+    let json = match v {
+        Int16(v) => J::from(*v),
+        Int32(v) => J::from(*v),
+        Int64(v) => J::from(*v),
+        Float32(v) => J::from(*v),
+        Float64(v) => J::from(*v),
+        Bool(v) => J::from(*v),
+        Json(v) => serde_json::from_str(v)?,
+        Set(items) | Array(items) | Tuple(items) => {
+            J::Array(items.iter().map(to_json).collect::<Result<_, _>>()?)
+        }
+        Vector(items) => J::Array(items.iter().map(|&x| J::from(x)).collect()),
+        Object { shape, fields } => J::Object(
+            shape
+                .elements
+                .iter()
+                .zip(fields)
+                .filter(|(el, _)| !el.flag_implicit)
+                .map(|(el, v)| {
+                    let name = if el.flag_link_property {
+                        format!("@{}", el.name)
+                    } else {
+                        el.name.clone()
+                    };
+                    Ok((name, opt_to_json(v.as_ref())?))
+                })
+                .collect::<Result<_, anyhow::Error>>()?,
+        ),
+        NamedTuple { shape, fields } => J::Object(
+            shape
+                .elements
+                .iter()
+                .zip(fields)
+                .map(|(el, v)| Ok((el.name.clone(), to_json(v)?)))
+                .collect::<Result<_, anyhow::Error>>()?,
+        ),
+        Range(rng) => serde_json::json!({
+            "lower": opt_to_json(rng.lower().map(|x| &**x))?,
+            "upper": opt_to_json(rng.upper().map(|x| &**x))?,
+            "inc_lower": rng.inc_lower(),
+            "inc_upper": rng.inc_upper(),
+            "empty": rng.is_empty(),
+        }),
+        _ => match scalar_to_string(v) {
+            Some(text) => J::String(text),
+            None => anyhow::bail!("{v:?} cannot be written to CSV"),
+        },
+    };
+    Ok(json)
+}
+
+fn opt_to_json(v: Option<&Value>) -> Result<serde_json::Value, anyhow::Error> {
+    v.map(to_json)
+        .transpose()
+        .map(|v| v.unwrap_or(serde_json::Value::Null))
+}
+
+#[cfg(test)]
+mod test {
+    use gel_protocol::codec::{ObjectShape, ShapeElement};
+    use gel_protocol::value::Value;
+
+    use super::{format_header, format_row, quote};
+    use crate::repl::CsvFlatten;
+
+    fn element(name: &str) -> ShapeElement {
+        ShapeElement {
+            flag_implicit: false,
+            flag_link_property: false,
+            flag_link: false,
+            cardinality: None,
+            name: name.into(),
+        }
+    }
+
+    fn object(fields: Vec<(&str, Option<Value>)>) -> Value {
+        let shape = ObjectShape::new(fields.iter().map(|(name, _)| element(name)).collect());
+        Value::Object {
+            shape,
+            fields: fields.into_iter().map(|(_, v)| v).collect(),
+        }
+    }
+
+    #[test]
+    fn quoting() {
+        assert_eq!(quote("plain"), "plain");
+        assert_eq!(quote("a,b"), "\"a,b\"");
+        assert_eq!(quote("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(quote("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn rows() {
+        let row = object(vec![
+            ("name", Some(Value::Str("Smith, John".into()))),
+            ("age", Some(Value::Int64(42))),
+            ("nickname", None),
+        ]);
+        assert_eq!(format_header(&row).unwrap(), "name,age,nickname");
+        assert_eq!(
+            format_row(&row, CsvFlatten::Json).unwrap(),
+            "\"Smith, John\",42,"
+        );
+        assert_eq!(format_header(&Value::Int64(1)), None);
+        assert_eq!(format_row(&Value::Int64(1), CsvFlatten::Json).unwrap(), "1");
+    }
+
+    #[test]
+    fn nested() {
+        let friends = Value::Set(vec![
+            object(vec![("name", Some(Value::Str("Alice".into())))]),
+            object(vec![("name", Some(Value::Str("Bob".into())))]),
+        ]);
+        let row = object(vec![
+            ("friends", Some(friends)),
+            (
+                "tags",
+                Some(Value::Array(vec![
+                    Value::Str("a".into()),
+                    Value::Str("b".into()),
+                ])),
+            ),
+        ]);
+        assert_eq!(
+            format_row(&row, CsvFlatten::Json).unwrap(),
+            r#""[{""name"":""Alice""},{""name"":""Bob""}]","[""a"",""b""]""#
+        );
+        assert_eq!(
+            format_row(&row, CsvFlatten::Join).unwrap(),
+            "Alice; Bob,a; b"
+        );
+    }
+}
@@ -17,11 +17,23 @@ pub fn print_query_error(
     query: &str,
     verbose: bool,
     source_name: &str,
+) -> Result<(), anyhow::Error> {
+    print_query_error_at(err, query, 0, verbose, source_name)
+}
+
+/// Same as `print_query_error`, but for a query that starts at byte `offset`
+/// of the larger `source` text, so that reported lines match the file
+pub fn print_query_error_at(
+    err: &Error,
+    source: &str,
+    offset: usize,
+    verbose: bool,
+    source_name: &str,
 ) -> Result<(), anyhow::Error> {
     let pstart = err.position_start();
     let pend = err.position_end();
     let (pstart, pend) = match (pstart, pend) {
-        (Some(s), Some(e)) => (s, e),
+        (Some(s), Some(e)) => (s + offset, e + offset),
         _ => {
             print::gel_error(err, verbose);
             return Ok(());
@@ -29,7 +41,7 @@ pub fn print_query_error(
     };
     let hint = err.hint().unwrap_or("error");
     let detail = err.details().map(|s| s.into());
-    let files = SimpleFile::new(source_name, query);
+    let files = SimpleFile::new(source_name, source);
     let context_error = err.contexts().rev().collect::<Vec<_>>();
     if !context_error.is_empty() {
         print::error!("{}", context_error.join(": "));
@@ -13,6 +13,7 @@ pub use crate::{error, msg, success, warn};
 pub use color::Highlight;
 pub use color::TERMINAL_LUMA;
 pub use error::print_query_error as query_error;
+pub use error::print_query_error_at as query_error_at;
 pub use error::print_query_warning as query_warning;
 pub use error::print_query_warnings as query_warnings;
 
@@ -234,6 +235,25 @@ where
     _native_format(rows, config, w, colors, Stdout {}).await
 }
 
+/// Formats rows the same way as `native_to_stdout` but into a string,
+/// colorless unless colors are forced in the config
+pub async fn native_to_string<S, I, E>(rows: S, config: &Config) -> Result<String, E>
+where
+    S: Stream<Item = Result<I, E>> + Send + Unpin,
+    I: FormatExt,
+    E: fmt::Debug + Error + 'static,
+{
+    let w = config.max_width.unwrap_or(80);
+    let mut out = String::new();
+    _native_format(rows, config, w, config.colors.unwrap_or(false), &mut out)
+        .await
+        .map_err(|e| match e {
+            PrintError::StreamErr { source } => source,
+            PrintError::PrintErr { source } => match source {},
+        })?;
+    Ok(out)
+}
+
 fn get_printer_string(prn: &mut Printer<&mut String>) -> String {
     prn.commit().unwrap_exc().unwrap_infallible();
     prn.flush_buf().unwrap_exc().unwrap_infallible();
@@ -415,6 +435,17 @@ where
     Ok(())
 }
 
+pub async fn table_to_string<S, I, E>(rows: S, config: &Config) -> Result<String, E>
+where
+    S: Stream<Item = Result<I, E>> + Send + Unpin,
+    I: FormatExt + Into<Value>,
+    E: fmt::Debug + Error + 'static,
+{
+    let w = config.max_width.unwrap_or(80);
+    let table = _table_format(rows, config, w, false).await?;
+    Ok(table.to_string())
+}
+
 async fn _native_format<S, I, E, O>(
     mut rows: S,
     config: &Config,
@@ -38,7 +38,7 @@ use crate::print::{self, Highlight, msg};
 use crate::project::{self, get_stash_path};
 use crate::question;
 use crate::table;
-use crate::{cloud, hooks};
+use crate::{cloud, git, hooks};
 
 #[allow(clippy::collapsible_else_if)]
 pub fn run(options: &Command, opts: &crate::options::Options) -> anyhow::Result<()> {
@@ -141,6 +141,11 @@ pub struct Command {
     /// Initialize in interactive mode
     #[arg(long)]
     pub interactive: bool,
+
+    /// Install a `post-checkout` git hook that switches to the branch
+    /// matching the git branch (see `branch sync-git`)
+    #[arg(long)]
+    pub git_hook: bool,
 }
 
 impl Command {
@@ -448,6 +453,10 @@ fn do_init(
     stash.database = handle.database.as_deref();
     stash.write(stash_dir)?;
 
+    if cmd.git_hook {
+        install_git_hook(project);
+    }
+
     if !opts.skip_hooks {
         hooks::on_action_sync("project.init.after", project)?;
     }
@@ -500,6 +509,10 @@ fn do_cloud_init(
     stash.database = handle.database.as_deref();
     stash.write(stash_dir)?;
 
+    if cmd.git_hook {
+        install_git_hook(project);
+    }
+
     if !opts.skip_hooks {
         hooks::on_action_sync("project.init.after", project)?;
     }
@@ -583,6 +596,10 @@ fn do_link(
     stash.database = inst.database.as_deref();
     stash.write(stash_dir)?;
 
+    if cmd.git_hook {
+        install_git_hook(project);
+    }
+
     if !opts.skip_hooks {
         hooks::on_action_sync("project.init.after", project)?;
     }
@@ -609,6 +626,19 @@ fn do_link(
     Ok(())
 }
 
+fn install_git_hook(project: &project::Context) {
+    match git::install_post_checkout_hook(&project.location.root) {
+        Ok(path) => msg!("Installed git hook {}", path.display()),
+        Err(e) => print::warn!("Cannot install git hook: {e:#}"),
+    }
+    if !project.manifest.project().git_branch_sync {
+        print::warn!(
+            "Set `git-branch-sync = true` in the [project] section of \
+             {MANIFEST_FILE_DISPLAY_NAME} to enable the hook."
+        );
+    }
+}
+
 fn directory_to_name(path: &Path, default: InstanceName) -> InstanceName {
     let path_stem = path.file_stem().and_then(|s| s.to_str());
     let Some(path_stem) = path_stem else {
@@ -1283,6 +1313,7 @@ async fn migrate_async(
             quiet: false,
             to_revision: None,
             dev_mode: false,
+            force_protected: false,
             single_transaction: false,
             no_index_build: false,
             conn: None,
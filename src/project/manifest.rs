@@ -53,6 +53,12 @@ pub struct Instance {
 #[serde(rename_all = "kebab-case")]
 pub struct Project {
     pub schema_dir: Option<PathBuf>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub git_branch_sync: bool,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub git_branch_map: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub protected_branches: Vec<String>,
 }
 
 impl Project {
@@ -62,6 +68,32 @@ impl Project {
             .unwrap_or_else(|| PathBuf::from("dbschema"))
     }
 
+    /// Returns the `protected-branches` pattern that matches the branch
+    pub fn protected_by(&self, branch: &str) -> Option<&str> {
+        self.protected_branches
+            .iter()
+            .find(|pattern| matches_pattern(pattern, branch))
+            .map(|p| &p[..])
+    }
+
+    /// Returns the branch that corresponds to the git branch, if syncing
+    /// branches with git is enabled
+    pub fn branch_for_git(&self, git_branch: &str) -> Option<String> {
+        if !self.git_branch_sync {
+            return None;
+        }
+        Some(self.map_git_branch(git_branch))
+    }
+
+    /// Maps a git branch name using `git-branch-map`, regardless of whether
+    /// syncing is enabled
+    pub fn map_git_branch(&self, git_branch: &str) -> String {
+        self.git_branch_map
+            .get(git_branch)
+            .cloned()
+            .unwrap_or_else(|| git_branch.to_string())
+    }
+
     pub fn resolve_schema_dir(&self, root: &Path) -> anyhow::Result<PathBuf> {
         let schema_dir = root.join(self.get_schema_dir());
 
@@ -143,11 +175,14 @@ pub fn read(path: &Path) -> anyhow::Result<Manifest> {
                     version: None,
                 }),
         },
-        project: Some(Project {
-            schema_dir: val
-                .project
-                .and_then(|p| p.schema_dir)
-                .map(|s| PathBuf::from(s.into_inner())),
+        project: Some(match val.project {
+            Some(project) => Project {
+                schema_dir: project.schema_dir.map(|s| PathBuf::from(s.into_inner())),
+                git_branch_sync: project.git_branch_sync,
+                git_branch_map: project.git_branch_map,
+                protected_branches: project.protected_branches,
+            },
+            None => Project::default(),
         }),
         hooks: val.hooks,
         hooks_extend: None,
@@ -253,6 +288,30 @@ pub fn modify_server_ver(config: &Path, ver: &Query) -> anyhow::Result<bool> {
     )
 }
 
+/// Matches a name against a pattern where `*` stands for any sequence of
+/// characters
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    if parts.peek().is_none() {
+        return rest.is_empty();
+    }
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    unreachable!()
+}
+
 fn warn_extra(extra: &BTreeMap<String, toml::Value>, prefix: &str) {
     for key in extra.keys() {
         log::warn!("Unknown config option `{}{}`", prefix, key.escape_default());
@@ -300,6 +359,12 @@ pub struct SrcInstance {
 pub struct SrcProject {
     #[serde(default)]
     pub schema_dir: Option<toml::Spanned<String>>,
+    #[serde(default)]
+    pub git_branch_sync: bool,
+    #[serde(default)]
+    pub git_branch_map: BTreeMap<String, String>,
+    #[serde(default)]
+    pub protected_branches: Vec<String>,
     #[serde(flatten)]
     #[allow(dead_code)]
     pub extra: BTreeMap<String, toml::Value>,
@@ -449,4 +514,43 @@ mod test {
     fn modify(src: &str, ver: &str) -> Option<String> {
         set_toml_version(src, &ver.parse().unwrap()).unwrap()
     }
+
+    #[test_case("main", "main" => true)]
+    #[test_case("main", "main2" => false)]
+    #[test_case("release/*", "release/1.0" => true)]
+    #[test_case("release/*", "release/" => true)]
+    #[test_case("release/*", "releases" => false)]
+    #[test_case("*-prod", "eu-prod" => true)]
+    #[test_case("*-prod", "eu-prod-copy" => false)]
+    #[test_case("a*b*c", "a-b-b-c" => true)]
+    #[test_case("a*b*c", "a-c-b" => false)]
+    #[test_case("*", "anything" => true)]
+    fn pattern(pattern: &str, name: &str) -> bool {
+        super::matches_pattern(pattern, name)
+    }
+
+    #[test]
+    fn git_branch_map() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gel.toml");
+        std::fs::write(
+            &path,
+            "[instance]\n\
+             server-version = \"6\"\n\
+             [project]\n\
+             git-branch-sync = true\n\
+             git-branch-map = {master = \"main\"}\n",
+        )
+        .unwrap();
+        let project = super::read(&path).unwrap().project();
+        assert_eq!(project.branch_for_git("master").as_deref(), Some("main"));
+        assert_eq!(
+            project.branch_for_git("feature").as_deref(),
+            Some("feature")
+        );
+
+        std::fs::write(&path, TOML_2_3).unwrap();
+        let project = super::read(&path).unwrap().project();
+        assert_eq!(project.branch_for_git("master"), None);
+    }
 }
@@ -448,6 +448,23 @@ impl Context {
     pub fn drop_project_lock(&mut self) {
         self.project_lock = None;
     }
+
+    /// Fails if the branch is listed in `protected-branches`, unless
+    /// `force` (i.e. `--force-protected`) is set
+    pub fn check_protected_branch(&self, branch: &str, force: bool) -> anyhow::Result<()> {
+        let project = self.manifest.project();
+        let Some(pattern) = project.protected_by(branch) else {
+            return Ok(());
+        };
+        if force {
+            print::warn!("Branch '{branch}' is protected, proceeding because of --force-protected");
+            return Ok(());
+        }
+        anyhow::bail!(
+            "Branch '{branch}' is protected (matches {pattern:?} in `protected-branches` \
+             of {MANIFEST_FILE_DISPLAY_NAME}). Use --force-protected to proceed anyway."
+        );
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -120,6 +120,7 @@ async fn sync(
             quiet: true,
             to_revision: None,
             dev_mode: false,
+            force_protected: false,
             single_transaction: false,
             no_index_build: false,
             conn: None,
@@ -139,6 +140,8 @@ async fn sync(
                 schema_dir: Some(mig_ctx.schema_dir.clone()),
             },
             squash: false,
+            from: None,
+            to: None,
             non_interactive: true,
             allow_unsafe: false,
             allow_empty: false,
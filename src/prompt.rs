@@ -19,6 +19,7 @@ use tokio::sync::oneshot::Sender;
 
 use crate::commands::backslash;
 use crate::completion;
+use crate::completion::schema::Schema;
 use crate::highlight;
 use crate::platform::editor_path;
 use crate::platform::pager_path;
@@ -54,6 +55,7 @@ pub enum Control {
     ViMode,
     EmacsMode,
     SetHistoryLimit(usize),
+    SetSchema(Option<Arc<Schema>>),
 }
 
 pub enum Input {
@@ -70,6 +72,7 @@ pub enum VarInput {
 
 pub struct EdgeqlHelper {
     styler: Styler,
+    schema: Option<Arc<Schema>>,
 }
 
 impl Helper for EdgeqlHelper {}
@@ -183,7 +186,7 @@ impl Completer for EdgeqlHelper {
         pos: usize,
         _ctx: &Context,
     ) -> Result<(usize, Vec<Self::Candidate>), ReadlineError> {
-        let comp = completion::complete(line, pos);
+        let comp = completion::complete(line, pos, self.schema.as_deref());
         if let Some((offset, options)) = comp {
             Ok((offset, options))
         } else {
@@ -228,7 +231,10 @@ pub fn save_history<H: Helper, I: History>(ed: &mut Editor<H, I>, name: &str) {
         .ok();
 }
 
-pub fn create_editor(config: &ConfigBuilder) -> anyhow::Result<Editor<EdgeqlHelper, FileHistory>> {
+pub fn create_editor(
+    config: &ConfigBuilder,
+    schema: Option<Arc<Schema>>,
+) -> anyhow::Result<Editor<EdgeqlHelper, FileHistory>> {
     let mut editor = Editor::<EdgeqlHelper, FileHistory>::with_config(config.clone().build())?;
     editor.bind_sequence(
         KeyEvent::new('\r', Modifiers::NONE),
@@ -244,6 +250,7 @@ pub fn create_editor(config: &ConfigBuilder) -> anyhow::Result<Editor<EdgeqlHelp
         .ok();
     editor.set_helper(Some(EdgeqlHelper {
         styler: Styler::new(),
+        schema,
     }));
     Ok(editor)
 }
@@ -295,21 +302,28 @@ pub fn main(mut control: Receiver<Control>) -> Result<(), anyhow::Error> {
     let config = Config::builder();
     let config = config.edit_mode(EditMode::Emacs);
     let mut config = config.completion_type(CompletionType::List);
-    let mut editor = create_editor(&config)?;
+    let mut schema = None;
+    let mut editor = create_editor(&config, schema.clone())?;
     'outer: loop {
         match control.blocking_recv() {
             None => break 'outer,
             Some(Control::ViMode) => {
                 config = config.edit_mode(EditMode::Vi);
-                editor = create_editor(&config)?;
+                editor = create_editor(&config, schema.clone())?;
             }
             Some(Control::EmacsMode) => {
                 config = config.edit_mode(EditMode::Emacs);
-                editor = create_editor(&config)?;
+                editor = create_editor(&config, schema.clone())?;
             }
             Some(Control::SetHistoryLimit(h)) => {
                 config = config.max_history_size(h)?;
-                editor = create_editor(&config)?;
+                editor = create_editor(&config, schema.clone())?;
+            }
+            Some(Control::SetSchema(new_schema)) => {
+                schema = new_schema;
+                if let Some(helper) = editor.helper_mut() {
+                    helper.schema = schema.clone();
+                }
             }
             Some(Control::EdgeqlInput {
                 prompt,
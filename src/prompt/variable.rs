@@ -438,6 +438,422 @@ impl VariableInput for Json {
     }
 }
 
+/// Values that EdgeQL writes as string literals (dates, durations, enums)
+/// have to be quoted inside of arrays, tuples and ranges, but are taken
+/// verbatim at the top level
+fn string_literal(input: &str, flags: InputFlags) -> IResult<&str, String, ParsingError> {
+    if flags.contains(InputFlags::FORCE_QUOTED_STRINGS) {
+        quoted_str(input)
+    } else {
+        Ok(("", input.trim().to_string()))
+    }
+}
+
+fn parse_naive<T>(
+    text: &str,
+    formats: &[&str],
+    parse: impl Fn(&str, &str) -> chrono::ParseResult<T>,
+) -> anyhow::Result<T> {
+    formats
+        .iter()
+        .find_map(|fmt| parse(text, fmt).ok())
+        .with_context(|| format!("expected format like {}", formats[0]))
+}
+
+/// Splits `1 year 2 months` or `3h 15m` into amounts and unit names
+fn duration_units(text: &str) -> anyhow::Result<Vec<(i64, String)>> {
+    let mut units = Vec::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let num_len = rest
+            .char_indices()
+            .find(|&(i, c)| !(c.is_ascii_digit() || (i == 0 && (c == '-' || c == '+'))))
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        let amount: i64 = rest[..num_len]
+            .parse()
+            .with_context(|| format!("expected a number at {rest:?}"))?;
+        rest = rest[num_len..].trim_start();
+        let unit_len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        if unit_len == 0 {
+            anyhow::bail!("expected a unit after {amount}");
+        }
+        units.push((amount, rest[..unit_len].to_ascii_lowercase()));
+        rest = rest[unit_len..].trim_start();
+    }
+    if units.is_empty() {
+        anyhow::bail!("expected a duration like `1 month 2 days`");
+    }
+    Ok(units)
+}
+
+#[derive(Debug)]
+pub struct Datetime;
+
+impl VariableInput for Datetime {
+    fn type_name(&self) -> Cow<'_, str> {
+        "datetime".into()
+    }
+    fn parse<'a>(&self, input: &'a str, flags: InputFlags) -> ParseResult<'a> {
+        context(
+            "datetime",
+            map_res(
+                |s| string_literal(s, flags),
+                |s| -> Result<Value, anyhow::Error> {
+                    let dt = chrono::DateTime::parse_from_rfc3339(&s).context(
+                        "expected timestamp with time zone like 2024-01-31T12:00:00+00:00",
+                    )?;
+                    let dt = model::Datetime::try_from(&dt.with_timezone(&chrono::Utc))?;
+                    Ok(Value::Datetime(dt))
+                },
+            ),
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct LocalDatetime;
+
+impl VariableInput for LocalDatetime {
+    fn type_name(&self) -> Cow<'_, str> {
+        "cal::local_datetime".into()
+    }
+    fn parse<'a>(&self, input: &'a str, flags: InputFlags) -> ParseResult<'a> {
+        context(
+            "local_datetime",
+            map_res(
+                |s| string_literal(s, flags),
+                |s| -> Result<Value, anyhow::Error> {
+                    let dt = parse_naive(
+                        &s,
+                        &[
+                            "%Y-%m-%dT%H:%M:%S%.f",
+                            "%Y-%m-%d %H:%M:%S%.f",
+                            "%Y-%m-%dT%H:%M",
+                            "%Y-%m-%d %H:%M",
+                        ],
+                        chrono::NaiveDateTime::parse_from_str,
+                    )?;
+                    Ok(Value::LocalDatetime(model::LocalDatetime::try_from(&dt)?))
+                },
+            ),
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct LocalDate;
+
+impl VariableInput for LocalDate {
+    fn type_name(&self) -> Cow<'_, str> {
+        "cal::local_date".into()
+    }
+    fn parse<'a>(&self, input: &'a str, flags: InputFlags) -> ParseResult<'a> {
+        context(
+            "local_date",
+            map_res(
+                |s| string_literal(s, flags),
+                |s| -> Result<Value, anyhow::Error> {
+                    let date = parse_naive(&s, &["%Y-%m-%d"], chrono::NaiveDate::parse_from_str)?;
+                    Ok(Value::LocalDate(model::LocalDate::try_from(&date)?))
+                },
+            ),
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct LocalTime;
+
+impl VariableInput for LocalTime {
+    fn type_name(&self) -> Cow<'_, str> {
+        "cal::local_time".into()
+    }
+    fn parse<'a>(&self, input: &'a str, flags: InputFlags) -> ParseResult<'a> {
+        context(
+            "local_time",
+            map_res(
+                |s| string_literal(s, flags),
+                |s| -> Result<Value, anyhow::Error> {
+                    let time = parse_naive(
+                        &s,
+                        &["%H:%M:%S%.f", "%H:%M"],
+                        chrono::NaiveTime::parse_from_str,
+                    )?;
+                    Ok(Value::LocalTime(model::LocalTime::from(&time)))
+                },
+            ),
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct Duration;
+
+impl VariableInput for Duration {
+    fn type_name(&self) -> Cow<'_, str> {
+        "duration".into()
+    }
+    fn parse<'a>(&self, input: &'a str, flags: InputFlags) -> ParseResult<'a> {
+        context(
+            "duration",
+            map_res(
+                |s| string_literal(s, flags),
+                |s| -> Result<Value, anyhow::Error> {
+                    let duration = s
+                        .parse::<model::Duration>()
+                        .context("expected duration like `1h 30m` or `PT1H30M`")?;
+                    Ok(Value::Duration(duration))
+                },
+            ),
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct RelativeDuration;
+
+impl VariableInput for RelativeDuration {
+    fn type_name(&self) -> Cow<'_, str> {
+        "cal::relative_duration".into()
+    }
+    fn parse<'a>(&self, input: &'a str, flags: InputFlags) -> ParseResult<'a> {
+        use model::RelativeDuration as D;
+
+        context(
+            "relative_duration",
+            map_res(
+                |s| string_literal(s, flags),
+                |s| -> Result<Value, anyhow::Error> {
+                    let mut result = D::from_micros(0);
+                    for (amount, unit) in duration_units(&s)? {
+                        let small = || i32::try_from(amount).context("value is out of range");
+                        let part = match &unit[..] {
+                            "y" | "year" | "years" => D::from_years(small()?),
+                            "mon" | "month" | "months" => D::from_months(small()?),
+                            "w" | "week" | "weeks" => D::from_days(small()?.saturating_mul(7)),
+                            "d" | "day" | "days" => D::from_days(small()?),
+                            "h" | "hour" | "hours" => D::from_hours(amount),
+                            "m" | "min" | "minute" | "minutes" => D::from_minutes(amount),
+                            "s" | "sec" | "second" | "seconds" => D::from_secs(amount),
+                            "ms" | "millisecond" | "milliseconds" => D::from_millis(amount),
+                            "us" | "microsecond" | "microseconds" => D::from_micros(amount),
+                            _ => anyhow::bail!("unknown unit {unit:?}"),
+                        };
+                        result = result.checked_add(part).context("value is out of range")?;
+                    }
+                    Ok(Value::RelativeDuration(result))
+                },
+            ),
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct DateDuration;
+
+impl VariableInput for DateDuration {
+    fn type_name(&self) -> Cow<'_, str> {
+        "cal::date_duration".into()
+    }
+    fn parse<'a>(&self, input: &'a str, flags: InputFlags) -> ParseResult<'a> {
+        use model::DateDuration as D;
+
+        context(
+            "date_duration",
+            map_res(
+                |s| string_literal(s, flags),
+                |s| -> Result<Value, anyhow::Error> {
+                    let mut result = D::from_days(0);
+                    for (amount, unit) in duration_units(&s)? {
+                        let amount = i32::try_from(amount).context("value is out of range")?;
+                        let part = match &unit[..] {
+                            "y" | "year" | "years" => D::from_years(amount),
+                            "mon" | "month" | "months" => D::from_months(amount),
+                            "w" | "week" | "weeks" => D::from_days(amount.saturating_mul(7)),
+                            "d" | "day" | "days" => D::from_days(amount),
+                            _ => anyhow::bail!(
+                                "unknown unit {unit:?}, expected years, months, weeks or days"
+                            ),
+                        };
+                        result = result.checked_add(part).context("value is out of range")?;
+                    }
+                    Ok(Value::DateDuration(result))
+                },
+            ),
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct Bytes;
+
+impl VariableInput for Bytes {
+    fn type_name(&self) -> Cow<'_, str> {
+        "bytes".into()
+    }
+    fn parse<'a>(&self, input: &'a str, _flags: InputFlags) -> ParseResult<'a> {
+        context("bytes", map(bytes_literal, |b| Value::Bytes(b.into())))(input)
+    }
+}
+
+/// Parses `b'...'` literal, same as the one printed for bytes values
+fn bytes_literal(input: &str) -> IResult<&str, Vec<u8>, ParsingError> {
+    fn mistake(description: String) -> nom::Err<ParsingError> {
+        Failure(ParsingError::Mistake {
+            kind: None,
+            description,
+        })
+    }
+
+    let (rest, quote) = preceded(
+        preceded(multispace0, char('b')),
+        alt((char('\''), char('"'))),
+    )(input)?;
+    let mut result = Vec::new();
+    let mut chars = rest.char_indices();
+    while let Some((pos, c)) = chars.next() {
+        match c {
+            _ if c == quote => return Ok((&rest[pos + 1..], result)),
+            '\\' => {
+                let byte = match chars.next().map(|(_, c)| c) {
+                    Some('x') => {
+                        let hex: String = chars.by_ref().take(2).map(|(_, c)| c).collect();
+                        u8::from_str_radix(&hex, 16)
+                            .map_err(|_| mistake(format!("Invalid escape \\x{hex}")))?
+                    }
+                    Some('\\') => b'\\',
+                    Some('\'') => b'\'',
+                    Some('"') => b'"',
+                    Some('n') => b'\n',
+                    Some('r') => b'\r',
+                    Some('t') => b'\t',
+                    Some(c) => return Err(mistake(format!("Invalid escape \\{c}"))),
+                    None => break,
+                };
+                result.push(byte);
+            }
+            _ if c.is_ascii() => result.push(c as u8),
+            _ => {
+                return Err(mistake(format!(
+                    "Non-ASCII character {c:?}, use \\x escapes instead"
+                )));
+            }
+        }
+    }
+    Err(mistake(format!("Missing end quote in '{input}'")))
+}
+
+#[derive(Debug)]
+pub struct Enum {
+    pub name: String,
+    pub members: Vec<String>,
+}
+
+impl VariableInput for Enum {
+    fn type_name(&self) -> Cow<'_, str> {
+        self.name.as_str().into()
+    }
+    fn parse<'a>(&self, input: &'a str, flags: InputFlags) -> ParseResult<'a> {
+        context(
+            "enum",
+            map_res(
+                |s| string_literal(s, flags),
+                |s| {
+                    if self.members.contains(&s) {
+                        Ok(Value::Enum(s.as_str().into()))
+                    } else {
+                        Err(ParsingError::Mistake {
+                            kind: None,
+                            description: format!("Expecting one of: {}", self.members.join(", ")),
+                        })
+                    }
+                },
+            ),
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct Range {
+    pub element_type: Arc<dyn VariableInput>,
+}
+
+impl VariableInput for Range {
+    fn type_name(&self) -> Cow<'_, str> {
+        format!("range<{}>", self.element_type.type_name()).into()
+    }
+    /// Accepts interval notation: `[1, 10)`, `(, 5]`, or `empty`
+    fn parse<'a>(&self, input: &'a str, _flags: InputFlags) -> ParseResult<'a> {
+        let bound = || {
+            opt(white_space(|s| {
+                self.element_type.parse(s, InputFlags::FORCE_QUOTED_STRINGS)
+            }))
+        };
+        context(
+            "range",
+            alt((
+                value(
+                    Value::Range(model::Range::empty()),
+                    white_space(tag_no_case("empty")),
+                ),
+                map(
+                    tuple((
+                        white_space(alt((char('['), char('(')))),
+                        bound(),
+                        char(','),
+                        bound(),
+                        preceded(multispace0, alt((char(']'), char(')')))),
+                    )),
+                    |(open, lower, _, upper, close)| {
+                        Value::Range(model::Range::new(
+                            lower.map(Box::new),
+                            upper.map(Box::new),
+                            open == '[',
+                            close == ']',
+                        ))
+                    },
+                ),
+            )),
+        )(input)
+    }
+}
+
+#[derive(Debug)]
+pub struct MultiRange {
+    pub element_type: Arc<dyn VariableInput>,
+}
+
+impl VariableInput for MultiRange {
+    fn type_name(&self) -> Cow<'_, str> {
+        format!("multirange<{}>", self.element_type.type_name()).into()
+    }
+    /// Accepts a list of ranges: `[[1, 5), [10,)]`
+    fn parse<'a>(&self, input: &'a str, _flags: InputFlags) -> ParseResult<'a> {
+        let range = Range {
+            element_type: self.element_type.clone(),
+        };
+        // multiranges are sent over the wire as arrays of ranges
+        context(
+            "multirange",
+            map(
+                preceded(
+                    white_space(char('[')),
+                    terminated(
+                        trailing_separated_list0(white_space(char(',')), |s| {
+                            range.parse(s, InputFlags::FORCE_QUOTED_STRINGS)
+                        }),
+                        preceded(multispace0, char(']')),
+                    ),
+                ),
+                Value::Array,
+            ),
+        )(input)
+    }
+}
+
 #[derive(Debug)]
 pub struct Array {
     pub element_type: Arc<dyn VariableInput>,
@@ -662,6 +1078,15 @@ fn format_parsing_error(e: nom::Err<ParsingError>) -> String {
     )
 }
 
+/// Parses a whole value non-interactively, e.g. one given on the command line
+pub fn parse_value(var_type: &dyn VariableInput, input: &str) -> anyhow::Result<Value> {
+    match var_type.parse(input, InputFlags::NONE) {
+        Ok((rest, value)) if rest.trim().is_empty() => Ok(value),
+        Ok((rest, _)) => anyhow::bail!("excess unparsed content {rest:?}"),
+        Err(e) => anyhow::bail!("{}", format_parsing_error(e).trim_start_matches(" -- ")),
+    }
+}
+
 pub struct VarHelper {
     var_type: Arc<dyn VariableInput>,
 }
@@ -756,8 +1181,9 @@ impl Completer for VarHelper {
 #[cfg(test)]
 mod tests {
     use crate::prompt::variable::{
-        Array, BigInt, Bool, Decimal, Float32, Float64, InputFlags, Int16, Int32, Int64, Json,
-        NamedTuple, ParseResult, Str, Tuple, Uuid, VariableInput,
+        Array, BigInt, Bool, Bytes, DateDuration, Datetime, Decimal, Duration, Enum, Float32,
+        Float64, InputFlags, Int16, Int32, Int64, Json, LocalDate, LocalTime, MultiRange,
+        NamedTuple, ParseResult, Range, RelativeDuration, Str, Tuple, Uuid, VariableInput,
     };
     use bigdecimal::BigDecimal;
     use gel_protocol::codec::NamedTupleShape;
@@ -1195,6 +1621,145 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_datetime() {
+        assert_value(
+            Datetime.parse("2020-11-04T16:22:18.347258Z", InputFlags::NONE),
+            Value::Datetime(model::Datetime::from_unix_micros(1604506938347258)),
+        );
+        assert_value(
+            Datetime.parse("2020-11-04T18:22:18.347258+02:00", InputFlags::NONE),
+            Value::Datetime(model::Datetime::from_unix_micros(1604506938347258)),
+        );
+        assert_value(
+            Array {
+                element_type: Arc::new(Datetime),
+            }
+            .parse("['2020-11-04T16:22:18.347258Z']", InputFlags::NONE),
+            Value::Array(vec![Value::Datetime(model::Datetime::from_unix_micros(
+                1604506938347258,
+            ))]),
+        );
+
+        // time zone is required
+        assert_error(Datetime.parse("2020-11-04T16:22:18", InputFlags::NONE));
+        assert_error(Datetime.parse("yesterday", InputFlags::NONE));
+    }
+
+    #[test]
+    fn test_local_types() {
+        assert!(LocalDate.parse("2024-02-29", InputFlags::NONE).is_ok());
+        assert_error(LocalDate.parse("2023-02-29", InputFlags::NONE));
+        assert!(LocalTime.parse("12:30", InputFlags::NONE).is_ok());
+        assert!(LocalTime.parse("12:30:15.25", InputFlags::NONE).is_ok());
+        assert_error(LocalTime.parse("25:00", InputFlags::NONE));
+    }
+
+    #[test]
+    fn test_durations() {
+        assert!(Duration.parse("1h 30m", InputFlags::NONE).is_ok());
+        assert_error(Duration.parse("forever", InputFlags::NONE));
+        assert_eq!(
+            RelativeDuration
+                .parse("1 year 2 months 3 days", InputFlags::NONE)
+                .unwrap()
+                .1,
+            RelativeDuration
+                .parse("1y 2mon 3d", InputFlags::NONE)
+                .unwrap()
+                .1,
+        );
+        assert_error(RelativeDuration.parse("1 fortnight", InputFlags::NONE));
+        assert_error(RelativeDuration.parse("days", InputFlags::NONE));
+        assert!(DateDuration.parse("2 weeks", InputFlags::NONE).is_ok());
+        assert_error(DateDuration.parse("2 hours", InputFlags::NONE));
+    }
+
+    #[test]
+    fn test_bytes() {
+        assert_value(
+            Bytes.parse("b'a\\x00\\'b'", InputFlags::NONE),
+            Value::Bytes(b"a\x00'b"[..].into()),
+        );
+        assert_excess(
+            Bytes.parse("b\"\"x", InputFlags::NONE),
+            Value::Bytes(b""[..].into()),
+        );
+        assert_error(Bytes.parse("abc", InputFlags::NONE));
+        assert_error(Bytes.parse("b'abc", InputFlags::NONE));
+        assert_error(Bytes.parse("b'\u{263A}'", InputFlags::NONE));
+    }
+
+    #[test]
+    fn test_enum() {
+        let color = Enum {
+            name: "default::Color".into(),
+            members: vec!["Red".into(), "Green".into()],
+        };
+        assert_value(
+            color.parse("Red", InputFlags::NONE),
+            Value::Enum("Red".into()),
+        );
+        assert_value(
+            color.parse("'Green'", InputFlags::FORCE_QUOTED_STRINGS),
+            Value::Enum("Green".into()),
+        );
+        assert_error(color.parse("Blue", InputFlags::NONE));
+        assert_eq!(color.type_name(), "default::Color");
+    }
+
+    #[test]
+    fn test_range() {
+        let range = Range {
+            element_type: Arc::new(Int64),
+        };
+        assert_value(
+            range.parse("[1, 10)", InputFlags::NONE),
+            Value::Range(model::Range::new(
+                Some(Box::new(Value::Int64(1))),
+                Some(Box::new(Value::Int64(10))),
+                true,
+                false,
+            )),
+        );
+        assert_value(
+            range.parse("( , 5 ]", InputFlags::NONE),
+            Value::Range(model::Range::new(
+                None,
+                Some(Box::new(Value::Int64(5))),
+                false,
+                true,
+            )),
+        );
+        assert_value(
+            range.parse("empty", InputFlags::NONE),
+            Value::Range(model::Range::empty()),
+        );
+        assert_error(range.parse("[1 10)", InputFlags::NONE));
+        assert_eq!(range.type_name(), "range<int64>");
+
+        let multirange = MultiRange {
+            element_type: Arc::new(Int64),
+        };
+        assert_value(
+            multirange.parse("[[1, 5), [10,)]", InputFlags::NONE),
+            Value::Array(vec![
+                Value::Range(model::Range::new(
+                    Some(Box::new(Value::Int64(1))),
+                    Some(Box::new(Value::Int64(5))),
+                    true,
+                    false,
+                )),
+                Value::Range(model::Range::new(
+                    Some(Box::new(Value::Int64(10))),
+                    None,
+                    true,
+                    false,
+                )),
+            ]),
+        );
+    }
+
     fn create_named_tuple_parser(
         shape: Vec<(&str, Arc<dyn VariableInput>)>,
     ) -> (NamedTupleShape, NamedTuple) {
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -21,6 +22,7 @@ use gel_protocol::value::Value;
 use crate::analyze;
 use crate::async_util::timeout;
 use crate::branding::{BRANDING, REPL_QUERY_TAG};
+use crate::completion::schema::{self, Schema};
 use crate::connect::Connection;
 use crate::connect::Connector;
 use crate::portable::ver;
@@ -53,6 +55,21 @@ pub enum OutputFormat {
     JsonLines,
     TabSeparated,
     Tabular,
+    Csv,
+}
+
+/// How nested data (links, arrays, tuples) is written in CSV output
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+#[value(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum CsvFlatten {
+    /// Write nested data as JSON
+    Json,
+    /// Join sets and arrays with `; `, write single-property objects as
+    /// the property value
+    Join,
 }
 
 #[derive(
@@ -87,6 +104,14 @@ pub struct PromptRpc {
     pub control: Sender<Control>,
 }
 
+/// Destination of query results set by `\o FILE`
+pub struct OutputRedirect {
+    pub path: PathBuf,
+    pub file: tokio::fs::File,
+    /// Also print results to the terminal
+    pub tee: bool,
+}
+
 pub struct LastAnalyze {
     #[allow(dead_code)]
     pub query: String,
@@ -99,6 +124,7 @@ pub struct State {
     pub verbose_errors: bool,
     pub last_error: Option<anyhow::Error>,
     pub last_analyze: Option<LastAnalyze>,
+    pub last_query: Option<String>,
     pub implicit_limit: Option<usize>,
     pub idle_transaction_timeout: EdbDuration,
     pub input_language: InputLanguage,
@@ -107,6 +133,7 @@ pub struct State {
     pub sql_output_format: OutputFormat,
     pub display_typenames: bool,
     pub print_stats: PrintStats,
+    pub csv_flatten: CsvFlatten,
     pub history_limit: usize,
     pub conn_params: Connector,
     pub branch: DatabaseBranch,
@@ -116,6 +143,9 @@ pub struct State {
     pub edgeql_state_desc: RawTypedesc,
     pub edgeql_state: EdgeqlState,
     pub current_branch: Option<String>,
+    pub schema: Option<Arc<Schema>>,
+    pub schema_outdated: bool,
+    pub output_redirect: Option<OutputRedirect>,
 }
 
 impl PromptRpc {
@@ -221,6 +251,7 @@ impl State {
         self.branch = branch;
         self.current_branch = Some(conn.get_current_branch().await?.to_string());
         self.connection = Some(conn);
+        self.schema_outdated = true;
         self.read_state();
         self.set_idle_transaction_timeout().await?;
         Ok(())
@@ -348,6 +379,35 @@ impl State {
             .ok()
             .context("cannot send to input thread")
     }
+    pub async fn refresh_schema(&mut self) -> anyhow::Result<()> {
+        if !self.schema_outdated {
+            return Ok(());
+        }
+        let Some(conn) = &mut self.connection else {
+            return Ok(());
+        };
+        // Introspection failing within a transaction would abort it,
+        // so refresh only after the transaction is finished
+        if !matches!(conn.transaction_state(), TransactionState::NotInTransaction) {
+            return Ok(());
+        }
+        let schema = match schema::fetch(conn).await {
+            Ok(schema) => Some(Arc::new(schema)),
+            Err(e) => {
+                log::warn!("Cannot fetch schema for completion: {e:#}");
+                None
+            }
+        };
+        // retry on the next prompt if introspection failed
+        self.schema_outdated = schema.is_none();
+        self.schema = schema.clone();
+        self.prompt
+            .control
+            .send(Control::SetSchema(schema))
+            .await
+            .ok()
+            .context("cannot send to input thread")
+    }
     pub async fn show_history(&mut self) -> anyhow::Result<()> {
         self.editor_cmd(|ack| Control::ShowHistory { ack }).await
     }
@@ -466,6 +526,7 @@ impl std::str::FromStr for OutputFormat {
             "json-pretty" => Ok(OutputFormat::JsonPretty),
             "json-lines" => Ok(OutputFormat::JsonLines),
             "tab-separated" => Ok(OutputFormat::TabSeparated),
+            "csv" => Ok(OutputFormat::Csv),
             "default" => Ok(OutputFormat::Default),
             _ => Err(anyhow::anyhow!("unsupported output mode {:?}", s)),
         }
@@ -475,9 +536,10 @@ impl std::str::FromStr for OutputFormat {
 impl From<OutputFormat> for IoFormat {
     fn from(val: OutputFormat) -> Self {
         match val {
-            OutputFormat::Default | OutputFormat::TabSeparated | OutputFormat::Tabular => {
-                IoFormat::Binary
-            }
+            OutputFormat::Default
+            | OutputFormat::TabSeparated
+            | OutputFormat::Tabular
+            | OutputFormat::Csv => IoFormat::Binary,
             OutputFormat::JsonLines | OutputFormat::JsonPretty => IoFormat::JsonElements,
             OutputFormat::Json => IoFormat::Json,
         }
@@ -526,6 +588,17 @@ impl OutputFormat {
             JsonLines => "json-lines",
             TabSeparated => "tab-separated",
             Tabular => "tabular",
+            Csv => "csv",
+        }
+    }
+}
+
+impl CsvFlatten {
+    pub fn as_str(&self) -> &'static str {
+        use CsvFlatten::*;
+        match self {
+            Json => "json",
+            Join => "join",
         }
     }
 }
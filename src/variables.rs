@@ -1,8 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::error::Error;
 use std::fmt;
+use std::path::Path;
 use std::sync::Arc;
 
+use anyhow::Context;
+
 use crate::prompt;
 use crate::prompt::variable::{self, VariableInput};
 use crate::repl;
@@ -49,6 +52,130 @@ pub async fn input_variables(
     }
 }
 
+/// Query argument values passed on the command line, kept as text until the
+/// parameter types are known
+#[derive(Debug, Default)]
+pub struct ArgValues {
+    values: BTreeMap<String, String>,
+    used: BTreeSet<String>,
+}
+
+impl ArgValues {
+    /// Merges arguments from a JSON file with `NAME=VALUE` pairs, the latter
+    /// taking precedence
+    ///
+    /// JSON strings are used as is, other JSON values are parsed from their
+    /// JSON text (which works for numbers, booleans, `json` and arrays).
+    pub fn new(args: &[(String, String)], file: Option<&Path>) -> anyhow::Result<ArgValues> {
+        let mut values = BTreeMap::new();
+        if let Some(path) = file {
+            let data =
+                std::fs::read_to_string(path).with_context(|| format!("cannot read {path:?}"))?;
+            let items: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&data)
+                .with_context(|| format!("{path:?} must contain a JSON object"))?;
+            for (name, value) in items {
+                let text = match value {
+                    // same as not passing the argument
+                    serde_json::Value::Null => continue,
+                    serde_json::Value::String(s) => s,
+                    value => value.to_string(),
+                };
+                values.insert(name, text);
+            }
+        }
+        values.extend(args.iter().cloned());
+        Ok(ArgValues {
+            values,
+            used: BTreeSet::new(),
+        })
+    }
+
+    /// Names of arguments not consumed by any query so far
+    pub fn unused(&self) -> Vec<&str> {
+        self.values
+            .keys()
+            .filter(|name| !self.used.contains(*name))
+            .map(|name| &name[..])
+            .collect()
+    }
+}
+
+/// Non-interactive counterpart of `input_variables`
+pub fn args_value(
+    desc: &Typedesc,
+    args: &mut ArgValues,
+    input_language: repl::InputLanguage,
+) -> Result<Value, anyhow::Error> {
+    // only for protocol < 0.12
+    if desc.is_empty_tuple() {
+        return Ok(Value::Tuple(Vec::new()));
+    }
+    match desc.root() {
+        Some(Descriptor::ObjectShape(obj)) => {
+            let mut fields = Vec::with_capacity(obj.elements.len());
+            let shape = obj.elements[..].into();
+            for el in obj.elements.iter() {
+                let optional = el.cardinality.map(|c| c.is_optional()).unwrap_or(false);
+                let name = match input_language {
+                    // SQL params are 1-based, so adjust the base
+                    repl::InputLanguage::Sql => (el
+                        .name
+                        .parse::<i32>()
+                        .expect("SQL argument names to be numeric")
+                        + 1)
+                    .to_string(),
+                    _ => el.name.to_owned(),
+                };
+                let Some(text) = args.values.get(&name) else {
+                    if optional {
+                        fields.push(None);
+                        continue;
+                    }
+                    anyhow::bail!(
+                        "missing value for query parameter ${name}, \
+                         pass it using `--arg {name}=VALUE`"
+                    );
+                };
+                let var_type = get_descriptor_type(desc.get(el.type_pos)?, desc)?;
+                let value = variable::parse_value(&*var_type, text).with_context(|| {
+                    format!("invalid value of ${name} ({})", var_type.type_name())
+                })?;
+                args.used.insert(name);
+                fields.push(Some(value));
+            }
+            Ok(Value::Object { shape, fields })
+        }
+        Some(root) => Err(anyhow::anyhow!("Unknown input type descriptor: {:?}", root)),
+        // Since protocol 0.12
+        None => Ok(Value::Nothing),
+    }
+}
+
+/// Parsers for each query parameter, in the order of the argument shape
+///
+/// Used to build arguments from text without prompting, see `args_value`.
+pub fn input_types(
+    desc: &Typedesc,
+) -> Result<(codec::ObjectShape, Vec<(String, Arc<dyn VariableInput>)>), anyhow::Error> {
+    match desc.root() {
+        Some(Descriptor::ObjectShape(obj)) => {
+            let types = obj
+                .elements
+                .iter()
+                .map(|el| {
+                    Ok((
+                        el.name.clone(),
+                        get_descriptor_type(desc.get(el.type_pos)?, desc)?,
+                    ))
+                })
+                .collect::<Result<_, anyhow::Error>>()?;
+            Ok((obj.elements[..].into(), types))
+        }
+        Some(root) => Err(anyhow::anyhow!("Unknown input type descriptor: {:?}", root)),
+        None => Ok((codec::ObjectShape::new(Vec::new()), Vec::new())),
+    }
+}
+
 fn get_descriptor_type<'a>(
     desc: &'a Descriptor,
     all: &'a Typedesc,
@@ -69,11 +196,31 @@ fn get_descriptor_type<'a>(
                 codec::STD_BOOL => Arc::new(variable::Bool),
                 codec::STD_JSON => Arc::new(variable::Json),
                 codec::STD_BIGINT => Arc::new(variable::BigInt),
+                codec::STD_BYTES => Arc::new(variable::Bytes),
+                codec::STD_DATETIME => Arc::new(variable::Datetime),
+                codec::CAL_LOCAL_DATETIME => Arc::new(variable::LocalDatetime),
+                codec::CAL_LOCAL_DATE => Arc::new(variable::LocalDate),
+                codec::CAL_LOCAL_TIME => Arc::new(variable::LocalTime),
+                codec::STD_DURATION => Arc::new(variable::Duration),
+                codec::CAL_RELATIVE_DURATION => Arc::new(variable::RelativeDuration),
+                codec::CAL_DATE_DURATION => Arc::new(variable::DateDuration),
                 _ => return Err(anyhow::anyhow!("Unimplemented input type {}", *s.id)),
             };
 
             Ok(var_type)
         }
+        Descriptor::Enumeration(enumeration) => Ok(Arc::new(variable::Enum {
+            name: enumeration.name.clone().unwrap_or_else(|| "enum".into()),
+            members: enumeration.members.clone(),
+        })),
+        Descriptor::Range(range) => {
+            let element_type = get_descriptor_type(all.get(range.type_pos)?, all)?;
+            Ok(Arc::new(variable::Range { element_type }))
+        }
+        Descriptor::MultiRange(range) => {
+            let element_type = get_descriptor_type(all.get(range.type_pos)?, all)?;
+            Ok(Arc::new(variable::MultiRange { element_type }))
+        }
         Descriptor::Array(arr) => {
             let element_type = get_descriptor_type(all.get(arr.type_pos)?, all)?;
             Ok(Arc::new(variable::Array { element_type }))
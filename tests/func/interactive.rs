@@ -99,3 +99,93 @@ fn warnings() {
     cmd.exp_string("Test warning please ignore").unwrap();
     cmd.exp_string("0").unwrap();
 }
+
+#[test]
+fn watch_last_query() {
+    let mut cmd = SERVER.admin_interactive();
+    let main = SERVER.default_branch();
+
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line("SELECT 'watch' ++ 'ed';\n").unwrap();
+    cmd.exp_string("watched").unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line("\\watch 0.2").unwrap();
+    cmd.exp_string("Every 200ms").unwrap();
+    cmd.exp_string("watched").unwrap();
+    cmd.exp_string("Every 200ms").unwrap();
+    cmd.exp_string("watched").unwrap();
+    cmd.send_control('c').unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+}
+
+#[test]
+fn watch_given_query() {
+    let mut cmd = SERVER.admin_interactive();
+    let main = SERVER.default_branch();
+
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    // without an interval, all arguments are the query
+    cmd.send_line("\\watch SELECT 40 + 2").unwrap();
+    cmd.exp_string("Every 2s").unwrap();
+    cmd.exp_string("42").unwrap();
+    cmd.send_control('c').unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line("\\watch 0.2 SELECT 6 * 7").unwrap();
+    cmd.exp_string("Every 200ms").unwrap();
+    cmd.exp_string("42").unwrap();
+    cmd.send_control('c').unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+}
+
+#[test]
+fn output_to_file() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("out.json");
+    let mut cmd = SERVER.admin_interactive();
+    let main = SERVER.default_branch();
+
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line("\\set output-format json-lines").unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line(&format!("\\o {}", path.display())).unwrap();
+    cmd.exp_string("Writing query results to").unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line("SELECT {'re', 'directed'};").unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line("\\o").unwrap();
+    cmd.exp_string("Stopped writing query results").unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line("SELECT 'terminal';").unwrap();
+    cmd.exp_string("\"terminal\"").unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+
+    assert_eq!(std::fs::read_to_string(&path)?, "\"re\"\n\"directed\"\n");
+    Ok(())
+}
+
+#[test]
+fn include_script() -> Result<(), Box<dyn Error>> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("script.edgeql");
+    std::fs::write(
+        &path,
+        "SELECT 'first' ++ 'query';\n\
+         # comment\n\
+         SELECT 'second' ++ 'query';\n\
+         \n\
+         SELECT 1 + 'x';\n\
+         SELECT 'not' ++ 'reached';\n",
+    )?;
+    let mut cmd = SERVER.admin_interactive();
+    let main = SERVER.default_branch();
+
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line(&format!("\\i {}", path.display())).unwrap();
+    cmd.exp_string("firstquery").unwrap();
+    cmd.exp_string("secondquery").unwrap();
+    cmd.exp_string("script.edgeql:5:").unwrap();
+    cmd.exp_string(&format!("{main}>")).unwrap();
+    cmd.send_line("SELECT 'after' ++ 'script';").unwrap();
+    cmd.exp_string("afterscript").unwrap();
+    Ok(())
+}
@@ -27,6 +27,39 @@ fn bare_status() {
         )));
 }
 
+#[test]
+fn bare_diff() {
+    SERVER
+        .admin_cmd()
+        .arg("database")
+        .arg("create")
+        .arg("diff")
+        .assert()
+        .success();
+    SERVER
+        .admin_cmd()
+        .arg("--branch=diff")
+        .arg("migration")
+        .arg("diff")
+        .arg("--schema-dir=tests/migrations/db1/bare")
+        .assert()
+        .code(2)
+        .stdout(contains("CREATE PROPERTY field1"))
+        .stderr(contains("Schema files and migrations are out of sync"));
+    // nothing is committed, so the same changes are reported again
+    SERVER
+        .admin_cmd()
+        .arg("--branch=diff")
+        .arg("migration")
+        .arg("diff")
+        .arg("--json")
+        .arg("--schema-dir=tests/migrations/db1/bare")
+        .assert()
+        .code(2)
+        .stdout(contains(r#""parent": "initial""#))
+        .stdout(contains("CREATE PROPERTY field1"));
+}
+
 #[test]
 fn initial() {
     crate::rm_migration_files("tests/migrations/db1/initial", &[2, 3]);
@@ -117,6 +150,31 @@ fn initial() {
             Last migration: \
             m12bulrbounwj3oj5xsspa7gj676azrog6ndi45iyuwrwzvawkxraa.\n",
         ));
+    SERVER
+        .admin_cmd()
+        .arg("--branch=initial")
+        .arg("migration")
+        .arg("status")
+        .arg("--json")
+        .arg("--schema-dir=tests/migrations/db1/initial")
+        .assert()
+        .success()
+        .stdout(contains(r#""status": "up-to-date""#))
+        .stdout(contains(r#""applied": true"#));
+    SERVER
+        .admin_cmd()
+        .arg("--branch=initial")
+        .arg("migration")
+        .arg("log")
+        .arg("--from-fs")
+        .arg("--json")
+        .arg("--schema-dir=tests/migrations/db1/initial")
+        .assert()
+        .success()
+        .stdout(contains(
+            r#""id": "m12bulrbounwj3oj5xsspa7gj676azrog6ndi45iyuwrwzvawkxraa""#,
+        ))
+        .stdout(contains(r#""parent_id": null"#));
     SERVER
         .admin_cmd()
         .arg("--branch=initial")
@@ -970,3 +1028,309 @@ fn unsafe_migrations() {
         .assert()
         .success();
 }
+
+#[test]
+fn three_way_merge() {
+    let project = tempfile::tempdir().unwrap();
+    let dir = project.path();
+    fs::write(
+        dir.join("edgedb.toml"),
+        "[edgedb]\nserver-version = \"1.0\"\n\n[project]\nschema-dir = \"./dbschema\"\n",
+    )
+    .unwrap();
+    let schema_dir = dir.join("dbschema");
+    let migrations_dir = schema_dir.join("migrations");
+    fs::create_dir_all(&migrations_dir).unwrap();
+    let write_schema = |types: &[&str]| {
+        let body: String = types.iter().map(|t| format!("    type {t};\n")).collect();
+        fs::write(
+            schema_dir.join("default.esdl"),
+            format!("module default {{\n{body}}}\n"),
+        )
+        .unwrap();
+    };
+    let migrate = |branch: &str| {
+        SERVER
+            .admin_cmd()
+            .arg(format!("--branch={branch}"))
+            .arg("migration")
+            .arg("create")
+            .arg("--non-interactive")
+            .current_dir(dir)
+            .assert()
+            .success();
+        SERVER
+            .admin_cmd()
+            .arg(format!("--branch={branch}"))
+            .arg("migrate")
+            .current_dir(dir)
+            .assert()
+            .success();
+    };
+    let file_names = || {
+        let mut names = fs::read_dir(&migrations_dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+
+    // common history
+    SERVER
+        .admin_cmd()
+        .arg("branch")
+        .arg("create")
+        .arg("--empty")
+        .arg("merge_target")
+        .assert()
+        .success();
+    write_schema(&["MergeBase"]);
+    migrate("merge_target");
+    SERVER
+        .admin_cmd()
+        .arg("branch")
+        .arg("create")
+        .arg("merge_source")
+        .arg("--from=merge_target")
+        .assert()
+        .success();
+
+    // a migration on each branch
+    write_schema(&["MergeBase", "MergeTarget"]);
+    migrate("merge_target");
+    let target_file = file_names().pop().unwrap();
+    assert!(target_file.starts_with("00002-"));
+    fs::remove_file(migrations_dir.join(&target_file)).unwrap();
+    write_schema(&["MergeBase", "MergeSource"]);
+    migrate("merge_source");
+    let source_file = file_names().pop().unwrap();
+    assert_ne!(source_file, target_file);
+
+    // schema files contain the changes of both branches
+    write_schema(&["MergeBase", "MergeSource", "MergeTarget"]);
+    let merge = || {
+        let mut cmd = SERVER.admin_cmd();
+        cmd.arg("--branch=merge_source")
+            .arg("branch")
+            .arg("merge")
+            .arg("merge_target")
+            .arg("--non-interactive")
+            .current_dir(dir);
+        cmd
+    };
+    merge()
+        .assert()
+        .failure()
+        .stderr(contains("use `--three-way`"));
+    merge()
+        .arg("--three-way")
+        .arg("--no-apply")
+        .assert()
+        .failure();
+    merge()
+        .arg("--three-way")
+        .assert()
+        .failure()
+        .stderr(contains("Use `--allow-data-loss`"));
+    // nothing was changed by the failed attempts
+    assert_eq!(file_names().last(), Some(&source_file));
+
+    merge()
+        .arg("--three-way")
+        .arg("--allow-data-loss")
+        .assert()
+        .success();
+
+    // target's migration is replayed, source's one is recomputed on top
+    let names = file_names();
+    assert_eq!(names.len(), 3);
+    assert_eq!(names[1], target_file);
+    assert!(names[2].starts_with("00003-"));
+    assert!(!names.contains(&source_file));
+
+    SERVER
+        .admin_cmd()
+        .arg("--branch=merge_source")
+        .arg("migration")
+        .arg("status")
+        .current_dir(dir)
+        .assert()
+        .success()
+        .stderr(contains("Database is up to date."));
+    SERVER
+        .admin_cmd()
+        .arg("--branch=merge_source")
+        .arg("query")
+        .arg(
+            "SELECT count(schema::ObjectType \
+             FILTER .name IN {'default::MergeSource', 'default::MergeTarget'})",
+        )
+        .assert()
+        .success()
+        .stdout("2\n");
+}
+
+#[test]
+fn lint_sarif() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("00002-m1abcde.edgeql");
+    fs::write(
+        &path,
+        "CREATE MIGRATION m1abcde\n    ONTO initial\n{\n  ALTER TYPE default::User {\n      \
+         ALTER PROPERTY age {\n          SET TYPE std::int32;\n      };\n  };\n};\n",
+    )
+    .unwrap();
+
+    // warnings don't fail the check by default
+    let output = crate::edgedb_cli_cmd()
+        .arg("migration")
+        .arg("lint")
+        .arg("--format=sarif")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(0));
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let run = &sarif["runs"][0];
+    assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 7);
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0]["ruleId"], "type-change");
+    assert_eq!(results[0]["level"], "warning");
+    let location = &results[0]["locations"][0]["physicalLocation"];
+    assert!(
+        location["artifactLocation"]["uri"]
+            .as_str()
+            .unwrap()
+            .ends_with("/00002-m1abcde.edgeql")
+    );
+    assert_eq!(location["region"]["startLine"], 6);
+    assert_eq!(location["region"]["startColumn"], 11);
+
+    crate::edgedb_cli_cmd()
+        .arg("migration")
+        .arg("lint")
+        .arg("--fail-on=warning")
+        .arg(&path)
+        .assert()
+        .code(1)
+        .stdout(contains("warning[type-change]"));
+}
+
+#[test]
+fn revert() {
+    let dir = tempfile::tempdir().unwrap();
+    let schema_dir = dir.path();
+    fs::create_dir(schema_dir.join("migrations")).unwrap();
+    fs::copy(
+        "tests/migrations/db1/initial/migrations/00001-m12bulr.edgeql",
+        schema_dir.join("migrations/00001-m12bulr.edgeql"),
+    )
+    .unwrap();
+    fs::write(
+        schema_dir.join("default.esdl"),
+        "module default {\n    type Type1 {\n        property field1 -> str;\n    };\n    \
+         type Type2 {\n        property field2 -> int64;\n    };\n};\n",
+    )
+    .unwrap();
+    let schema_arg = format!("--schema-dir={}", schema_dir.display());
+    let migration_files = || {
+        let mut names = fs::read_dir(schema_dir.join("migrations"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+
+    SERVER
+        .admin_cmd()
+        .arg("branch")
+        .arg("create")
+        .arg("--empty")
+        .arg("revert_test")
+        .assert()
+        .success();
+    SERVER
+        .admin_cmd()
+        .arg("--branch=revert_test")
+        .arg("migration")
+        .arg("create")
+        .arg("--non-interactive")
+        .arg(&schema_arg)
+        .assert()
+        .success();
+    SERVER
+        .admin_cmd()
+        .arg("--branch=revert_test")
+        .arg("migrate")
+        .arg(&schema_arg)
+        .assert()
+        .success();
+    assert_eq!(migration_files().len(), 2);
+
+    SERVER
+        .admin_cmd()
+        .arg("--branch=revert_test")
+        .arg("migration")
+        .arg("revert")
+        .arg("--to-revision=m12bulr")
+        .arg("--dry-run")
+        .arg(&schema_arg)
+        .assert()
+        .success()
+        .stdout(contains("DROP TYPE default::Type2"))
+        .stderr(contains("data loss[drop-type]"));
+    assert_eq!(migration_files().len(), 2);
+
+    SERVER
+        .admin_cmd()
+        .arg("--branch=revert_test")
+        .arg("migration")
+        .arg("revert")
+        .arg("--to-revision=m12bulr")
+        .arg("--non-interactive")
+        .arg(&schema_arg)
+        .assert()
+        .success();
+
+    let files = migration_files();
+    assert_eq!(files.len(), 3);
+    assert!(files[2].starts_with("00003-"));
+    let text = fs::read_to_string(schema_dir.join("migrations").join(&files[2])).unwrap();
+    assert!(text.contains("DROP TYPE default::Type2"));
+    assert!(!text.contains("Type1"));
+
+    SERVER
+        .admin_cmd()
+        .arg("--branch=revert_test")
+        .arg("migration")
+        .arg("status")
+        .arg(&schema_arg)
+        .assert()
+        .success()
+        .stderr(contains("Database is up to date."));
+    SERVER
+        .admin_cmd()
+        .arg("--branch=revert_test")
+        .arg("query")
+        .arg("SELECT count(schema::ObjectType FILTER .name LIKE 'default::Type%')")
+        .assert()
+        .success()
+        .stdout("1\n");
+
+    SERVER
+        .admin_cmd()
+        .arg("--branch=revert_test")
+        .arg("migration")
+        .arg("revert")
+        .arg("--to-revision=m12bulr")
+        .arg("--non-interactive")
+        .arg(&schema_arg)
+        .assert()
+        .success()
+        .stderr(contains("Nothing to revert."));
+    assert_eq!(migration_files().len(), 3);
+}
@@ -44,6 +44,72 @@ fn list_indexes() {
         .success();
 }
 
+#[test]
+fn list_functions_and_operators() {
+    SERVER
+        .admin_cmd()
+        .arg("list")
+        .arg("functions")
+        .arg("--system")
+        .arg("^std::len$")
+        .assert()
+        .context("list_functions", "signatures of std::len")
+        .success()
+        .stdout(predicates::str::contains(
+            "std::len\t(str: std::str)\tstd::int64\tImmutable",
+        ));
+
+    SERVER
+        .admin_cmd()
+        .arg("list")
+        .arg("operators")
+        .arg("--system")
+        .arg("-v")
+        .arg("^std::\\+\\+$")
+        .assert()
+        .context("list_operators", "concatenation operators")
+        .success()
+        .stdout(predicates::str::contains("std::++\tinfix\t"));
+}
+
+#[test]
+fn list_globals_and_policies() {
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("CREATE GLOBAL list_user_id: int64")
+        .arg(
+            "CREATE TYPE ListPolicyDoc { CREATE PROPERTY owner: int64; \
+             CREATE ACCESS POLICY own ALLOW SELECT, UPDATE \
+             USING (.owner ?= GLOBAL list_user_id); }",
+        )
+        .assert()
+        .context("list_globals_and_policies", "create schema")
+        .success();
+
+    SERVER
+        .admin_cmd()
+        .arg("list")
+        .arg("globals")
+        .arg("list_user_id")
+        .assert()
+        .context("list_globals_and_policies", "global is not set")
+        .success()
+        .stdout("default::list_user_id\tstd::int64\t{}\n");
+
+    SERVER
+        .admin_cmd()
+        .arg("list")
+        .arg("policies")
+        .arg("ListPolicyDoc")
+        .assert()
+        .context("list_globals_and_policies", "policies of a type")
+        .success()
+        .stdout(predicates::str::contains(
+            "default::ListPolicyDoc\town\tallow\t",
+        ));
+}
+
 #[test]
 fn database_create_wipe_drop() {
     SERVER
@@ -353,6 +419,102 @@ fn branch_commands() {
     assert_eq!(get_current_instance_branch(instance_name), "test_branch_3");
 }
 
+#[test]
+fn branch_diff() {
+    for (branch, ddl) in [
+        (
+            "test_diff_1",
+            "CREATE TYPE DiffUser { CREATE PROPERTY name: str; }",
+        ),
+        (
+            "test_diff_2",
+            "CREATE TYPE DiffUser { CREATE PROPERTY name: str; CREATE PROPERTY age: int64; }; \
+             CREATE SCALAR TYPE DiffSlug EXTENDING str;",
+        ),
+    ] {
+        SERVER
+            .admin_cmd()
+            .arg("branch")
+            .arg("create")
+            .arg("--empty")
+            .arg(branch)
+            .assert()
+            .context("branch_diff", "create branch")
+            .success();
+        SERVER
+            .admin_cmd()
+            .arg(format!("--branch={branch}"))
+            .arg("query")
+            .arg(ddl)
+            .assert()
+            .context("branch_diff", "create schema")
+            .success();
+    }
+
+    SERVER
+        .admin_cmd()
+        .arg("branch")
+        .arg("diff")
+        .arg("test_diff_1")
+        .arg("test_diff_2")
+        .arg("--ddl")
+        .assert()
+        .context("branch_diff", "diff with ddl")
+        .success()
+        .stdout(
+            predicates::str::contains("--- test_diff_1\n+++ test_diff_2\n")
+                .and(predicates::str::contains(
+                    "@@ default::DiffUser (changed) @@",
+                ))
+                .and(predicates::str::is_match(r"(?m)^\+.*property age: std::int64;$").unwrap())
+                .and(predicates::str::is_match(r"(?m)^ .*property name: std::str;$").unwrap())
+                .and(predicates::str::contains("@@ default::DiffSlug (added) @@"))
+                .and(predicates::str::contains(
+                    "# DDL to migrate 'test_diff_1' to 'test_diff_2'",
+                ))
+                .and(predicates::str::contains("CREATE PROPERTY age"))
+                .and(predicates::str::contains(
+                    "CREATE SCALAR TYPE default::DiffSlug",
+                ))
+                .and(predicates::str::contains("DiffUser (removed)").not()),
+        );
+
+    SERVER
+        .admin_cmd()
+        .arg("branch")
+        .arg("diff")
+        .arg("test_diff_1")
+        .arg("test_diff_1")
+        .assert()
+        .context("branch_diff", "same branch")
+        .failure();
+
+    SERVER
+        .admin_cmd()
+        .arg("--branch=test_diff_2")
+        .arg("query")
+        .arg(
+            "DROP TYPE DiffUser; DROP SCALAR TYPE DiffSlug; \
+              CREATE TYPE DiffUser { CREATE PROPERTY name: str; }",
+        )
+        .assert()
+        .context("branch_diff", "make schemas equal")
+        .success();
+    SERVER
+        .admin_cmd()
+        .arg("branch")
+        .arg("diff")
+        .arg("test_diff_1")
+        .arg("test_diff_2")
+        .assert()
+        .context("branch_diff", "identical schemas")
+        .success()
+        .stdout("")
+        .stderr(predicates::str::contains(
+            "Schemas of 'test_diff_1' and 'test_diff_2' are identical.",
+        ));
+}
+
 #[test]
 fn hash_password() {
     crate::edgedb_cli_cmd()
@@ -410,3 +572,171 @@ fn warnings() {
         )
         .context("warnings", "print warnings from migrations");
 }
+
+#[test]
+fn query_args() {
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("--output-format=json-lines")
+        .arg("--arg=name=world")
+        .arg("--arg=n=3")
+        .arg("SELECT 'hello ' ++ <str>$name ++ <str>(<int64>$n + 1)")
+        .assert()
+        .context("query_args", "typed arguments")
+        .success()
+        .stdout("\"hello world4\"\n");
+
+    let dir = tempfile::tempdir().unwrap();
+    let args_file = dir.path().join("args.json");
+    std::fs::write(
+        &args_file,
+        r#"{"ids": [1, 2], "when": "2024-01-31T12:00:00Z"}"#,
+    )
+    .unwrap();
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("--output-format=json-lines")
+        .arg("--args-file")
+        .arg(&args_file)
+        .arg("SELECT (sum(array_unpack(<array<int64>>$ids)), <str><datetime>$when)")
+        .assert()
+        .context("query_args", "arguments from file")
+        .success()
+        .stdout("[3, \"2024-01-31T12:00:00+00:00\"]\n");
+
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("SELECT <int64>$n")
+        .assert()
+        .context("query_args", "missing argument")
+        .failure()
+        .stderr(predicates::str::contains("--arg n=VALUE"));
+
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("--arg=n=abc")
+        .arg("SELECT <int64>$n")
+        .assert()
+        .context("query_args", "invalid argument")
+        .failure()
+        .stderr(predicates::str::contains("invalid value of $n (int64)"));
+}
+
+#[test]
+fn csv_output() {
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("--output-format=csv")
+        .arg("SELECT {name := 'Smith, John', tags := ['a', 'b']}")
+        .assert()
+        .context("csv_output", "nested values as json")
+        .success()
+        .stdout("name,tags\r\n\"Smith, John\",\"[\"\"a\"\",\"\"b\"\"]\"\r\n");
+
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("--output-format=csv")
+        .arg("--csv-flatten=join")
+        .arg("SELECT {name := 'x', tags := ['a', 'b']}")
+        .assert()
+        .context("csv_output", "nested values joined")
+        .success()
+        .stdout("name,tags\r\nx,a; b\r\n");
+}
+
+#[test]
+fn import_csv() {
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("CREATE TYPE ImportUser { CREATE REQUIRED PROPERTY name: str; CREATE PROPERTY age: int64; }")
+        .assert()
+        .context("import_csv", "create type")
+        .success();
+
+    let dir = tempfile::tempdir().unwrap();
+    let data = dir.path().join("users.csv");
+    let rejects = dir.path().join("rejects.csv");
+    std::fs::write(
+        &data,
+        "name,age\nAlice,30\nBob,oops\n,5\nDave,40,extra\nCarol,\n",
+    )
+    .unwrap();
+    SERVER
+        .admin_cmd()
+        .arg("import")
+        .arg("--type=ImportUser")
+        .arg("--reject-file")
+        .arg(&rejects)
+        .arg(&data)
+        .assert()
+        .context("import_csv", "import with bad rows")
+        .success()
+        .stderr(predicates::str::contains(
+            "Imported 2 rows into default::ImportUser, 3 rows rejected",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(&rejects).unwrap(),
+        "name,age\nBob,oops\n,5\nDave,40,extra\n"
+    );
+
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("--output-format=json-lines")
+        .arg("SELECT ImportUser { name, age } ORDER BY .name")
+        .assert()
+        .context("import_csv", "check imported data")
+        .success()
+        .stdout("{\"name\": \"Alice\", \"age\": 30}\n{\"name\": \"Carol\", \"age\": null}\n");
+}
+
+#[test]
+fn describe_schema_json() {
+    SERVER
+        .admin_cmd()
+        .arg("query")
+        .arg("CREATE TYPE SchemaJsonDoc { CREATE REQUIRED PROPERTY title: str; }")
+        .assert()
+        .context("describe_schema_json", "create type")
+        .success();
+
+    let output = SERVER
+        .admin_cmd()
+        .arg("describe")
+        .arg("schema")
+        .arg("--format=json")
+        .arg("--module=default")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(doc["version"], 1);
+    assert_eq!(doc["modules"].as_array().unwrap().len(), 1);
+    let types = doc["modules"][0]["object_types"].as_array().unwrap();
+    let typ = types
+        .iter()
+        .find(|t| t["name"] == "default::SchemaJsonDoc")
+        .expect("type is described");
+    assert_eq!(typ["pointers"][0]["name"], "title");
+    assert_eq!(typ["pointers"][0]["required"], true);
+
+    let dir = tempfile::tempdir().unwrap();
+    SERVER
+        .admin_cmd()
+        .arg("describe")
+        .arg("schema")
+        .arg("--format=json")
+        .arg("--output-dir")
+        .arg(dir.path())
+        .assert()
+        .context("describe_schema_json", "per-module files")
+        .success();
+    assert!(dir.path().join("default.json").exists());
+}